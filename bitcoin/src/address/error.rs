@@ -4,8 +4,9 @@ use core::fmt;
 
 use internals::write_err;
 
-use crate::address::{Address, NetworkUnchecked};
-use crate::prelude::String;
+use crate::address::{Address, AddressInner, NetworkUnchecked};
+use crate::consensus::encode;
+use crate::prelude::{String, Vec};
 use crate::script::{witness_program, witness_version};
 use crate::Network;
 
@@ -56,6 +57,106 @@ impl From<witness_version::TryFromError> for FromScriptError {
     fn from(e: witness_version::TryFromError) -> Self { Self::WitnessVersion(e) }
 }
 
+/// Error decoding a list of addresses with [`consensus::decode_addresses`].
+///
+/// [`consensus::decode_addresses`]: crate::consensus::decode_addresses
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecodeAddressesError {
+    /// Invalid consensus encoding.
+    Decode(encode::Error),
+    /// A script pubkey can not be represented as an address.
+    FromScript {
+        /// The index of the script pubkey in the list.
+        index: usize,
+        /// The underlying error.
+        error: FromScriptError,
+    },
+}
+
+internals::impl_from_infallible!(DecodeAddressesError);
+
+impl fmt::Display for DecodeAddressesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DecodeAddressesError::*;
+
+        match *self {
+            Decode(ref e) => write_err!(f, "invalid consensus encoding"; e),
+            FromScript { index, ref error } =>
+                write_err!(f, "script pubkey at index {} is not an address", index; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeAddressesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use DecodeAddressesError::*;
+
+        match *self {
+            Decode(ref e) => Some(e),
+            FromScript { ref error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<encode::Error> for DecodeAddressesError {
+    fn from(e: encode::Error) -> Self { Self::Decode(e) }
+}
+
+/// Error decoding an address from bytes with [`Address::from_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FromBytesError {
+    /// The encoding has an invalid length.
+    InvalidLength(usize),
+    /// The address type byte is unknown.
+    UnknownType(u8),
+    /// The network byte is unknown.
+    UnknownNetwork(u8),
+    /// A witness version construction error.
+    WitnessVersion(witness_version::TryFromError),
+    /// A witness program error.
+    WitnessProgram(witness_program::Error),
+}
+
+internals::impl_from_infallible!(FromBytesError);
+
+impl fmt::Display for FromBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use FromBytesError::*;
+
+        match *self {
+            InvalidLength(len) => write!(f, "invalid address encoding length: {}", len),
+            UnknownType(tag) => write!(f, "unknown address type: {}", tag),
+            UnknownNetwork(network) => write!(f, "unknown address network: {}", network),
+            WitnessVersion(ref e) => write_err!(f, "witness version construction error"; e),
+            WitnessProgram(ref e) => write_err!(f, "witness program error"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromBytesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use FromBytesError::*;
+
+        match *self {
+            InvalidLength(_) | UnknownType(_) | UnknownNetwork(_) => None,
+            WitnessVersion(ref e) => Some(e),
+            WitnessProgram(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<witness_version::TryFromError> for FromBytesError {
+    fn from(e: witness_version::TryFromError) -> Self { Self::WitnessVersion(e) }
+}
+
+impl From<witness_program::Error> for FromBytesError {
+    fn from(e: witness_program::Error) -> Self { Self::WitnessProgram(e) }
+}
+
 /// Address type is either invalid or not supported in rust-bitcoin.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -76,6 +177,8 @@ impl std::error::Error for UnknownAddressTypeError {
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ParseError {
+    /// The string is empty or only contains whitespace.
+    Empty,
     /// Base58 error.
     Base58(base58::Error),
     /// Bech32 segwit decoding error.
@@ -94,15 +197,42 @@ pub enum ParseError {
     InvalidLegacyPrefix(InvalidLegacyPrefixError),
     /// Address's network differs from required one.
     NetworkValidation(NetworkValidationError),
+    /// Address is not valid for any of the required networks.
+    NetworkSetValidation(NetworkSetValidationError),
 }
 
 internals::impl_from_infallible!(ParseError);
 
+impl ParseError {
+    /// Returns the encoding of the address string that failed to parse.
+    ///
+    /// An empty string is reported as [`Encoding::Unknown`] since no decoder ran on it.
+    pub fn encoding(&self) -> Encoding {
+        use ParseError::*;
+
+        match *self {
+            Empty => Encoding::Unknown,
+            Base58(_)
+            | LegacyAddressTooLong(_)
+            | InvalidBase58PayloadLength(_)
+            | InvalidLegacyPrefix(_) => Encoding::Base58,
+            Bech32(_) | WitnessVersion(_) | WitnessProgram(_) | UnknownHrp(_) => Encoding::Bech32,
+            NetworkValidation(NetworkValidationError { ref address, .. })
+            | NetworkSetValidation(NetworkSetValidationError { ref address, .. }) =>
+                match address.0 {
+                    AddressInner::Segwit { .. } => Encoding::Bech32,
+                    AddressInner::P2pkh { .. } | AddressInner::P2sh { .. } => Encoding::Base58,
+                },
+        }
+    }
+}
+
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use ParseError::*;
 
         match *self {
+            Empty => f.write_str("empty address string"),
             Base58(ref e) => write_err!(f, "base58 error"; e),
             Bech32(ref e) => write_err!(f, "bech32 segwit decoding error"; e),
             WitnessVersion(ref e) => write_err!(f, "witness version conversion/parsing error"; e),
@@ -112,6 +242,7 @@ impl fmt::Display for ParseError {
             InvalidBase58PayloadLength(ref e) => write_err!(f, "legacy address base58 data"; e),
             InvalidLegacyPrefix(ref e) => write_err!(f, "legacy address base58 prefix"; e),
             NetworkValidation(ref e) => write_err!(f, "validation error"; e),
+            NetworkSetValidation(ref e) => write_err!(f, "validation error"; e),
         }
     }
 }
@@ -122,6 +253,7 @@ impl std::error::Error for ParseError {
         use ParseError::*;
 
         match *self {
+            Empty => None,
             Base58(ref e) => Some(e),
             Bech32(ref e) => Some(e),
             WitnessVersion(ref e) => Some(e),
@@ -131,10 +263,15 @@ impl std::error::Error for ParseError {
             InvalidBase58PayloadLength(ref e) => Some(e),
             InvalidLegacyPrefix(ref e) => Some(e),
             NetworkValidation(ref e) => Some(e),
+            NetworkSetValidation(ref e) => Some(e),
         }
     }
 }
 
+impl From<NetworkSetValidationError> for ParseError {
+    fn from(e: NetworkSetValidationError) -> Self { Self::NetworkSetValidation(e) }
+}
+
 impl From<base58::Error> for ParseError {
     fn from(e: base58::Error) -> Self { Self::Base58(e) }
 }
@@ -171,6 +308,28 @@ impl From<NetworkValidationError> for ParseError {
     fn from(e: NetworkValidationError) -> Self { Self::NetworkValidation(e) }
 }
 
+/// The string encoding of an address.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// Base58check, used by legacy (P2PKH and P2SH) addresses.
+    Base58,
+    /// Bech32 or bech32m, used by segwit addresses.
+    Bech32,
+    /// The encoding could not be determined, e.g. because the string was empty.
+    Unknown,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Encoding::Base58 => f.write_str("base58"),
+            Encoding::Bech32 => f.write_str("bech32"),
+            Encoding::Unknown => f.write_str("unknown"),
+        }
+    }
+}
+
 /// Unknown HRP error.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -205,6 +364,38 @@ impl fmt::Display for NetworkValidationError {
 #[cfg(feature = "std")]
 impl std::error::Error for NetworkValidationError {}
 
+/// Address is not valid for any of the required networks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkSetValidationError {
+    /// Networks that were accepted.
+    pub(crate) required: Vec<Network>,
+    /// The address itself.
+    pub(crate) address: Address<NetworkUnchecked>,
+}
+
+impl NetworkSetValidationError {
+    /// Returns the networks that were accepted.
+    pub fn required(&self) -> &[Network] { &self.required }
+}
+
+impl fmt::Display for NetworkSetValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "address ")?;
+        fmt::Display::fmt(&self.address.0, f)?;
+        write!(f, " is not valid on any of [")?;
+        for (i, network) in self.required.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", network)?;
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NetworkSetValidationError {}
+
 /// Decoded base58 data was an invalid length.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InvalidBase58PayloadLengthError {
@@ -267,3 +458,29 @@ impl fmt::Display for InvalidLegacyPrefixError {
 
 #[cfg(feature = "std")]
 impl std::error::Error for InvalidLegacyPrefixError {}
+
+/// Witness program is valid but spending from it is not standard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonStandardError {
+    /// The non-standard witness program.
+    pub(crate) program: witness_program::WitnessProgram,
+}
+
+impl NonStandardError {
+    /// Returns the non-standard witness program.
+    pub fn witness_program(&self) -> witness_program::WitnessProgram { self.program }
+}
+
+impl fmt::Display for NonStandardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "non-standard segwit {} program of length {}",
+            self.program.version(),
+            self.program.program().len()
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NonStandardError {}
@@ -27,12 +27,14 @@
 //! ```
 
 pub mod error;
+pub mod silent_payment;
 
 use core::fmt;
 use core::marker::PhantomData;
 use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
-use bech32::primitives::gf32::Fe32;
 use bech32::primitives::hrp::Hrp;
 use hashes::{sha256, HashEngine};
 use secp256k1::{Secp256k1, Verification, XOnlyPublicKey};
@@ -46,19 +48,25 @@ use crate::crypto::key::{
     CompressedPublicKey, PubkeyHash, PublicKey, TweakedPublicKey, UntweakedPublicKey,
 };
 use crate::network::{Network, NetworkKind};
-use crate::prelude::{String, ToOwned};
-use crate::script::witness_program::WitnessProgram;
+use crate::opcodes::all::{
+    OP_CHECKSIG, OP_DUP, OP_EQUAL, OP_EQUALVERIFY, OP_HASH160, OP_PUSHBYTES_20,
+};
+use crate::opcodes::Opcode;
+use crate::prelude::{DisplayHex, String, ToOwned, Vec};
+use crate::script::witness_program::{self, WitnessProgram};
 use crate::script::witness_version::WitnessVersion;
 use crate::script::{
     self, RedeemScriptSizeError, Script, ScriptBuf, ScriptHash, WScriptHash, WitnessScriptSizeError,
 };
 use crate::taproot::TapNodeHash;
+use crate::transaction::{InputWeightPrediction, TxIn};
+use crate::Weight;
 
 #[rustfmt::skip]                // Keep public re-exports separate.
 #[doc(inline)]
 pub use self::error::{
-        FromScriptError, InvalidBase58PayloadLengthError, InvalidLegacyPrefixError, LegacyAddressTooLongError,
-        NetworkValidationError, ParseError, UnknownAddressTypeError, UnknownHrpError,
+        DecodeAddressesError, Encoding, FromBytesError, FromScriptError, InvalidBase58PayloadLengthError, InvalidLegacyPrefixError, LegacyAddressTooLongError,
+        NetworkSetValidationError, NetworkValidationError, NonStandardError, ParseError, UnknownAddressTypeError, UnknownHrpError,
 };
 
 /// The different types of addresses.
@@ -77,6 +85,26 @@ pub enum AddressType {
     P2tr,
 }
 
+impl AddressType {
+    /// Returns the length in bytes of the script pubkey of an address of this type.
+    ///
+    /// See also [`Address::script_pubkey_len`].
+    pub fn spk_size(&self) -> usize {
+        match *self {
+            // OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG
+            AddressType::P2pkh => 25,
+            // OP_HASH160 <20 bytes> OP_EQUAL
+            AddressType::P2sh => 23,
+            // OP_0 <20 bytes>
+            AddressType::P2wpkh => 22,
+            // OP_0 <32 bytes>
+            AddressType::P2wsh => 34,
+            // OP_1 <32 bytes>
+            AddressType::P2tr => 34,
+        }
+    }
+}
+
 impl fmt::Display for AddressType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(match *self {
@@ -169,8 +197,7 @@ impl fmt::Display for AddressInner {
             }
             Segwit { program, hrp } => {
                 let hrp = hrp.to_hrp();
-                let version = Fe32::try_from(program.version().to_num())
-                    .expect("version nums 0-16 are valid fe32 values");
+                let version = program.version_fe32();
                 let program = program.program().as_ref();
 
                 if fmt.alternate() {
@@ -223,6 +250,12 @@ impl KnownHrp {
         }
     }
 
+    /// Returns true if this is the regtest HRP.
+    ///
+    /// Note that regtest and the test networks share the same [`NetworkKind`] so converting to
+    /// `NetworkKind` loses this distinction.
+    pub fn is_regtest(self) -> bool { self == Self::Regtest }
+
     /// Converts, infallibly a known HRP to a [`bech32::Hrp`].
     fn to_hrp(self) -> Hrp {
         match self {
@@ -384,10 +417,126 @@ impl<V: NetworkValidation> Address<V> {
     pub fn as_unchecked(&self) -> &Address<NetworkUnchecked> {
         unsafe { &*(self as *const Address<V> as *const Address<NetworkUnchecked>) }
     }
+
+    /// Returns the human-readable part of a segwit address, `None` for legacy addresses.
+    ///
+    /// Legacy addresses use the same prefixes for regtest as for testnet so only segwit addresses
+    /// can be identified as regtest addresses.
+    pub fn known_hrp(&self) -> Option<KnownHrp> {
+        match self.0 {
+            AddressInner::Segwit { hrp, .. } => Some(hrp),
+            AddressInner::P2pkh { .. } | AddressInner::P2sh { .. } => None,
+        }
+    }
+
+    /// Encodes the address as a compact byte string.
+    ///
+    /// This is an alternative to the string encoding for storing addresses in binary formats, it
+    /// can be decoded with [`Address::from_bytes`]. The first byte is the address type: `0` for
+    /// P2PKH, `1` for P2SH and `2` for segwit.
+    ///
+    /// - For P2PKH and P2SH the type is followed by the network kind (`0` for mainnet, `1` for
+    ///   testnets) and the 20 byte hash.
+    /// - For segwit the type is followed by the HRP (`0` for mainnet, `1` for testnets, `2` for
+    ///   regtest), the witness version and the witness program.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        use AddressInner::*;
+
+        let mut bytes = Vec::with_capacity(3 + witness_program::MAX_SIZE);
+        match self.0 {
+            P2pkh { hash, network } => {
+                bytes.push(TAG_P2PKH);
+                bytes.push(network_kind_to_byte(network));
+                bytes.extend_from_slice(hash.as_byte_array());
+            }
+            P2sh { hash, network } => {
+                bytes.push(TAG_P2SH);
+                bytes.push(network_kind_to_byte(network));
+                bytes.extend_from_slice(hash.as_byte_array());
+            }
+            Segwit { ref program, hrp } => {
+                bytes.push(TAG_SEGWIT);
+                bytes.push(match hrp {
+                    KnownHrp::Mainnet => 0,
+                    KnownHrp::Testnets => 1,
+                    KnownHrp::Regtest => 2,
+                });
+                bytes.push(program.version().to_num());
+                bytes.extend_from_slice(program.program().as_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+/// Address type tags used by [`Address::to_bytes`].
+const TAG_P2PKH: u8 = 0;
+const TAG_P2SH: u8 = 1;
+const TAG_SEGWIT: u8 = 2;
+
+fn network_kind_to_byte(network: NetworkKind) -> u8 {
+    match network {
+        NetworkKind::Main => 0,
+        NetworkKind::Test => 1,
+    }
 }
 
 /// Methods and functions that can be called only on `Address<NetworkChecked>`.
 impl Address {
+    /// Parses an address string and checks that it is valid for `network`.
+    ///
+    /// This is equivalent to parsing an `Address<NetworkUnchecked>` and then calling
+    /// [`require_network`](Address<NetworkUnchecked>::require_network) on it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bitcoin::{Address, Network};
+    ///
+    /// let address = Address::parse("32iVBEu4dxkUQk9dJbZUiBiQdmypcEyJRf", Network::Bitcoin).unwrap();
+    /// assert_eq!(address.to_string(), "32iVBEu4dxkUQk9dJbZUiBiQdmypcEyJRf");
+    ///
+    /// assert!(Address::parse("32iVBEu4dxkUQk9dJbZUiBiQdmypcEyJRf", Network::Testnet).is_err());
+    /// ```
+    #[inline]
+    pub fn parse(s: &str, network: Network) -> Result<Address, ParseError> {
+        s.parse::<Address<NetworkUnchecked>>()?.require_network(network)
+    }
+
+    /// Parses an address string entered by a user and checks that it is valid for `network`.
+    ///
+    /// Surrounding whitespace and an optional `bitcoin:` URI scheme are removed and bech32
+    /// addresses are lowercased, base58 addresses are case sensitive so are left untouched. The
+    /// result is then parsed as with [`Address::parse`].
+    ///
+    /// This is intended for user input only, protocol data should always be parsed strictly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bitcoin::{Address, Network};
+    ///
+    /// let s = " bitcoin:BC1QVZVKJN4Q3NSZQXRV3NRAGA2R822XJTY3YKVKUW\n";
+    /// let address = Address::parse_lenient(s, Network::Bitcoin).unwrap();
+    /// assert_eq!(address.to_string(), "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw");
+    /// ```
+    pub fn parse_lenient(s: &str, network: Network) -> Result<Address, ParseError> {
+        const SCHEME: &str = "bitcoin:";
+
+        let s = s.trim();
+        let s = match s.get(..SCHEME.len()) {
+            Some(scheme) if scheme.eq_ignore_ascii_case(SCHEME) => &s[SCHEME.len()..],
+            _ => s,
+        };
+
+        // No base58 address starts with a bech32 prefix so lowercasing these is unambiguous.
+        if has_segwit_prefix(s) {
+            Address::parse(&s.to_ascii_lowercase(), network)
+        } else {
+            Address::parse(s, network)
+        }
+    }
+
     /// Creates a pay to (compressed) public key hash address from a public key.
     ///
     /// This is the preferred non-witness type address.
@@ -416,6 +565,8 @@ impl Address {
     ///
     /// The `hash` pre-image (redeem script) must not exceed 520 bytes in length
     /// otherwise outputs created from the returned address will be un-spendable.
+    /// Prefer [`Address::p2sh`] when the redeem script is known, it checks the size and the hash
+    /// can be retrieved later using [`Address::script_hash`].
     pub fn p2sh_from_hash(hash: ScriptHash, network: impl Into<NetworkKind>) -> Address {
         Self(AddressInner::P2sh { hash, network: network.into() }, PhantomData)
     }
@@ -428,15 +579,42 @@ impl Address {
         Address::from_witness_program(program, hrp)
     }
 
+    /// Creates a witness pay to public key address from a secp256k1 public key.
+    ///
+    /// A secp256k1 public key is always serialized compressed, so this is the same as
+    /// [`Address::p2wpkh`] with the key wrapped in a [`CompressedPublicKey`].
+    pub fn p2wpkh_from_secp_pubkey(pk: secp256k1::PublicKey, hrp: impl Into<KnownHrp>) -> Self {
+        Address::p2wpkh(CompressedPublicKey(pk), hrp)
+    }
+
     /// Creates a pay to script address that embeds a witness pay to public key.
     ///
     /// This is a segwit address type that looks familiar (as p2sh) to legacy clients.
     pub fn p2shwpkh(pk: CompressedPublicKey, network: impl Into<NetworkKind>) -> Address {
-        let builder = script::Builder::new().push_int(0).push_slice(pk.wpubkey_hash());
-        let script_hash = builder.as_script().script_hash().expect("script is less than 520 bytes");
+        let redeem_script = Address::p2shwpkh_redeem_script(pk);
+        let script_hash = redeem_script.script_hash().expect("script is less than 520 bytes");
         Address::p2sh_from_hash(script_hash, network)
     }
 
+    /// Creates a pay to script address that embeds a witness pay to public key from a secp256k1
+    /// public key.
+    ///
+    /// This is the same as [`Address::p2shwpkh`] with the key wrapped in a [`CompressedPublicKey`].
+    pub fn p2shwpkh_from_secp_pubkey(
+        pk: secp256k1::PublicKey,
+        network: impl Into<NetworkKind>,
+    ) -> Address {
+        Address::p2shwpkh(CompressedPublicKey(pk), network)
+    }
+
+    /// Returns the redeem script of a [`p2shwpkh`](Address::p2shwpkh) address.
+    ///
+    /// This is the `OP_0 <20-byte-hash>` script that must be pushed in the script sig when
+    /// spending, the signature and public key go in the witness.
+    pub fn p2shwpkh_redeem_script(pk: CompressedPublicKey) -> ScriptBuf {
+        ScriptBuf::new_p2wpkh(pk.wpubkey_hash())
+    }
+
     /// Creates a witness pay to script hash address.
     pub fn p2wsh(
         witness_script: &Script,
@@ -491,6 +669,21 @@ impl Address {
         Address(inner, PhantomData)
     }
 
+    /// Creates an address from a witness program, rejecting programs that can't be spent using
+    /// standard transactions.
+    ///
+    /// Version 0 programs must be 20 or 32 bytes long (already enforced by [`WitnessProgram`])
+    /// and version 1 programs must be 32 bytes long. Later versions are accepted as is.
+    pub fn from_witness_program_standard(
+        program: WitnessProgram,
+        hrp: impl Into<KnownHrp>,
+    ) -> Result<Address, NonStandardError> {
+        if program.version() == WitnessVersion::V1 && program.program().len() != 32 {
+            return Err(NonStandardError { program });
+        }
+        Ok(Address::from_witness_program(program, hrp))
+    }
+
     /// Gets the address type of the address.
     ///
     /// # Returns
@@ -514,6 +707,54 @@ impl Address {
         }
     }
 
+    /// Returns true if this address has the same type as `other`.
+    ///
+    /// Useful to pick a change address that looks like the payment address. Segwit addresses
+    /// without an [`AddressType`] (future versions) are the same type if they have the same
+    /// witness version and program length.
+    pub fn same_type_as(&self, other: &Address) -> bool {
+        match (self.address_type(), other.address_type()) {
+            (Some(this), Some(other)) => this == other,
+            (None, None) => match (&self.0, &other.0) {
+                (
+                    AddressInner::Segwit { program: this, .. },
+                    AddressInner::Segwit { program: other, .. },
+                ) =>
+                    this.version() == other.version()
+                        && this.program().len() == other.program().len(),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns the expected shape of the scriptSig and witness needed to spend this address.
+    ///
+    /// Useful for fee estimation before the transaction is signed. Assumes the largest possible
+    /// DER-encoded signature, a compressed public key for P2PKH and a key path spend with the
+    /// default sighash type for P2TR.
+    ///
+    /// Returns `None` for P2SH, P2WSH and future witness versions since the spending script is
+    /// not known from the address alone.
+    pub fn spending_scriptsig_template(&self) -> Option<InputWeightPrediction> {
+        match self.address_type()? {
+            AddressType::P2pkh => Some(InputWeightPrediction::P2PKH_COMPRESSED_MAX),
+            AddressType::P2wpkh => Some(InputWeightPrediction::P2WPKH_MAX),
+            AddressType::P2tr => Some(InputWeightPrediction::P2TR_KEY_DEFAULT_SIGHASH),
+            AddressType::P2sh | AddressType::P2wsh => None,
+        }
+    }
+
+    /// Returns the estimated weight an input spending this address adds to a transaction.
+    ///
+    /// This includes the outpoint, sequence, scriptSig and witness but not the segwit marker and
+    /// flag or a change in the size of the input count. Estimated using the same assumptions as
+    /// [`spending_scriptsig_template`](Self::spending_scriptsig_template), hence also returns
+    /// `None` for the same address types.
+    pub fn spending_weight(&self) -> Option<Weight> {
+        self.spending_scriptsig_template().map(|template| TxIn::BASE_WEIGHT + template.weight())
+    }
+
     /// Gets the address data from this address.
     pub fn to_address_data(&self) -> AddressData {
         use AddressData::*;
@@ -555,6 +796,51 @@ impl Address {
         }
     }
 
+    /// Returns the witness version if this is a segwit address, `None` for legacy addresses.
+    pub fn segwit_version(&self) -> Option<WitnessVersion> {
+        use AddressInner::*;
+
+        match self.0 {
+            Segwit { ref program, hrp: _ } => Some(program.version()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this address might wrap a segwit program (nested segwit).
+    ///
+    /// A P2SH address only commits to the hash of its redeem script, so an address wrapping a
+    /// segwit program (e.g. P2SH-P2WPKH) cannot be told apart from any other P2SH address. This
+    /// therefore returns true for every P2SH address, use [`Address::is_nested_segwit_for`] to
+    /// check against a known redeem script.
+    pub fn could_be_nested_segwit(&self) -> bool {
+        use AddressInner::*;
+
+        matches!(self.0, P2sh { .. })
+    }
+
+    /// Returns true if this is a P2SH address for `redeem_script` and it is a segwit program.
+    pub fn is_nested_segwit_for(&self, redeem_script: &Script) -> bool {
+        match self.script_hash() {
+            Some(hash) if redeem_script.is_witness_program() =>
+                redeem_script.script_hash() == Ok(hash),
+            _ => false,
+        }
+    }
+
+    /// Returns the components of a segwit address as encoded by bech32, `None` for legacy addresses.
+    ///
+    /// The returned parts are the human-readable part, the witness version and the witness
+    /// program, which is useful for validation beyond what is done when parsing.
+    pub fn bech32_parts(&self) -> Option<(Hrp, WitnessVersion, &[u8])> {
+        use AddressInner::*;
+
+        match self.0 {
+            Segwit { ref program, hrp } =>
+                Some((hrp.to_hrp(), program.version(), program.program().as_bytes())),
+            _ => None,
+        }
+    }
+
     /// Checks whether or not the address is following Bitcoin standardness rules when
     /// *spending* from this address. *NOT* to be called by senders.
     ///
@@ -576,19 +862,13 @@ impl Address {
         params: impl AsRef<Params>,
     ) -> Result<Address, FromScriptError> {
         let network = params.as_ref().network;
-        if script.is_p2pkh() {
-            let bytes = script.as_bytes()[3..23].try_into().expect("statically 20B long");
-            let hash = PubkeyHash::from_byte_array(bytes);
+        if let Some(hash) = script.extract_p2pkh_hash() {
             Ok(Address::p2pkh(hash, network))
-        } else if script.is_p2sh() {
-            let bytes = script.as_bytes()[2..22].try_into().expect("statically 20B long");
-            let hash = ScriptHash::from_byte_array(bytes);
+        } else if let Some(hash) = script.extract_p2sh_hash() {
             Ok(Address::p2sh_from_hash(hash, network))
-        } else if script.is_witness_program() {
-            let opcode = script.first_opcode().expect("is_witness_program guarantees len > 4");
-
-            let version = WitnessVersion::try_from(opcode)?;
-            let program = WitnessProgram::new(version, &script.as_bytes()[2..])?;
+        } else if let Some(version) = script.witness_version() {
+            let bytes = script.witness_program_bytes().expect("checked by witness_version");
+            let program = WitnessProgram::new(version, bytes)?;
             Ok(Address::from_witness_program(program, network))
         } else {
             Err(FromScriptError::UnrecognizedScript)
@@ -596,6 +876,11 @@ impl Address {
     }
 
     /// Generates a script pubkey spending to this address.
+    ///
+    /// The script pubkey is not stored inside the address so this allocates. This is also the
+    /// reason `Address` does not implement `AsRef<Script>`. To avoid the allocation when checking
+    /// an existing script use [`Address::matches_script_pubkey`], and to get the script pubkey size
+    /// use [`Address::script_pubkey_len`].
     pub fn script_pubkey(&self) -> ScriptBuf {
         use AddressInner::*;
         match self.0 {
@@ -609,6 +894,71 @@ impl Address {
         }
     }
 
+    /// Appends the script pubkey spending to this address to `buf`.
+    ///
+    /// Produces the same bytes as [`Address::script_pubkey`] but lets callers reuse a buffer when
+    /// building many outputs.
+    pub fn script_pubkey_into(&self, buf: &mut Vec<u8>) {
+        use AddressInner::*;
+
+        buf.reserve(self.script_pubkey_len());
+        match self.0 {
+            P2pkh { ref hash, network: _ } => {
+                buf.extend_from_slice(&[
+                    OP_DUP.to_u8(),
+                    OP_HASH160.to_u8(),
+                    OP_PUSHBYTES_20.to_u8(),
+                ]);
+                buf.extend_from_slice(hash.as_ref());
+                buf.extend_from_slice(&[OP_EQUALVERIFY.to_u8(), OP_CHECKSIG.to_u8()]);
+            }
+            P2sh { ref hash, network: _ } => {
+                buf.extend_from_slice(&[OP_HASH160.to_u8(), OP_PUSHBYTES_20.to_u8()]);
+                buf.extend_from_slice(hash.as_ref());
+                buf.push(OP_EQUAL.to_u8());
+            }
+            Segwit { ref program, hrp: _ } => {
+                let prog = program.program();
+                // Witness programs are at most 40 bytes so the push opcode is the length.
+                buf.push(Opcode::from(program.version()).to_u8());
+                buf.push(prog.len() as u8);
+                buf.extend_from_slice(prog.as_bytes());
+            }
+        }
+    }
+
+    /// Returns the length in bytes of the script pubkey spending to this address.
+    ///
+    /// Equivalent to `self.script_pubkey().len()` but does not allocate.
+    pub fn script_pubkey_len(&self) -> usize {
+        use AddressInner::*;
+        match self.0 {
+            // OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG
+            P2pkh { .. } => 25,
+            // OP_HASH160 <20 bytes> OP_EQUAL
+            P2sh { .. } => 23,
+            // <version> <program>
+            Segwit { ref program, hrp: _ } => 2 + program.program().len(),
+        }
+    }
+
+    /// Computes the SHA256 hash of the script pubkey of this address.
+    ///
+    /// This is the "script hash" used by Electrum servers to index addresses. Note that the
+    /// Electrum protocol uses the hex encoding of the *reversed* hash bytes, use
+    /// [`Address::electrum_scripthash_hex`] to get it.
+    pub fn electrum_scripthash(&self) -> sha256::Hash {
+        sha256::Hash::hash(self.script_pubkey().as_bytes())
+    }
+
+    /// Returns the Electrum script hash as sent to the server in `blockchain.scripthash.*`
+    /// requests, the lower hex encoding of the reversed [`Address::electrum_scripthash`].
+    pub fn electrum_scripthash_hex(&self) -> String {
+        let mut bytes = self.electrum_scripthash().to_byte_array();
+        bytes.reverse();
+        bytes.to_lower_hex_string()
+    }
+
     /// Creates a URI string *bitcoin:address* optimized to be encoded in QR codes.
     ///
     /// If the address is bech32, the address becomes uppercase.
@@ -621,7 +971,8 @@ impl Address {
     /// parsed as case-insensitive many wallets got this wrong and don't parse correctly.
     /// [See compatibility table.](https://github.com/btcpayserver/btcpayserver/issues/2110)
     ///
-    /// If you want to avoid allocation you can use alternate display instead:
+    /// To avoid the allocation use [`Address::write_qr_uri`], or use alternate display to write
+    /// only the address:
     /// ```
     /// # use core::fmt::Write;
     /// # const ADDRESS: &str = "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4";
@@ -638,6 +989,42 @@ impl Address {
     /// ```
     pub fn to_qr_uri(&self) -> String { format!("bitcoin:{:#}", self) }
 
+    /// Writes the URI returned by [`Address::to_qr_uri`] to `w` without allocating.
+    pub fn write_qr_uri<W: fmt::Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
+        write!(w, "bitcoin:{:#}", self)
+    }
+
+    /// Returns the number of characters in the string representation of this address.
+    ///
+    /// This is the same as `self.to_string().len()` but does not allocate.
+    pub fn display_len(&self) -> usize {
+        /// Counts the characters written to it.
+        struct Counter(usize);
+
+        impl fmt::Write for Counter {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += s.len();
+                Ok(())
+            }
+        }
+
+        match self.0 {
+            AddressInner::Segwit { ref program, hrp } => bech32::segwit::encoded_length(
+                hrp.to_hrp(),
+                program.version_fe32(),
+                program.program().as_bytes(),
+            )
+            .expect("witness program is at most 40 bytes"),
+            // Base58 length depends on the leading zero bytes so we have to encode it.
+            _ => {
+                let mut counter = Counter(0);
+                fmt::write(&mut counter, format_args!("{}", self.0))
+                    .expect("counter does not error");
+                counter.0
+            }
+        }
+    }
+
     /// Returns true if the given pubkey is directly related to the address payload.
     ///
     /// This is determined by directly comparing the address payload with either the
@@ -670,12 +1057,28 @@ impl Address {
                 &script.as_bytes()[3..23] == <PubkeyHash as AsRef<[u8; 20]>>::as_ref(hash),
             P2sh { ref hash, network: _ } if script.is_p2sh() =>
                 &script.as_bytes()[2..22] == <ScriptHash as AsRef<[u8; 20]>>::as_ref(hash),
-            Segwit { ref program, hrp: _ } if script.is_witness_program() =>
-                &script.as_bytes()[2..] == program.program().as_bytes(),
+            Segwit { ref program, hrp: _ } if script.is_witness_program() => script
+                .witness_version()
+                .map_or(false, |version| program.matches_bytes(version, &script.as_bytes()[2..])),
             P2pkh { .. } | P2sh { .. } | Segwit { .. } => false,
         }
     }
 
+    /// Returns true if this address and `other` produce the same script pubkey.
+    ///
+    /// The network is not part of the script pubkey, so this is true for the same payload
+    /// encoded for different networks (e.g. a mainnet and a testnet address) even though the
+    /// addresses themselves differ. This doesn't make any allocations.
+    pub fn same_script_pubkey(&self, other: &Address) -> bool {
+        use AddressInner::*;
+        match (&self.0, &other.0) {
+            (P2pkh { hash: this, .. }, P2pkh { hash: other, .. }) => this == other,
+            (P2sh { hash: this, .. }, P2sh { hash: other, .. }) => this == other,
+            (Segwit { program: this, .. }, Segwit { program: other, .. }) => this == other,
+            _ => false,
+        }
+    }
+
     /// Returns the "payload" for this address.
     ///
     /// The "payload" is the useful stuff excluding serialization prefix, the exact payload is
@@ -696,6 +1099,44 @@ impl Address {
 
 /// Methods that can be called only on `Address<NetworkUnchecked>`.
 impl Address<NetworkUnchecked> {
+    /// Decodes an address encoded with [`Address::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FromBytesError> {
+        let (&tag, rest) = bytes.split_first().ok_or(FromBytesError::InvalidLength(0))?;
+        let (&network, data) = rest.split_first().ok_or(FromBytesError::InvalidLength(1))?;
+
+        let inner = match tag {
+            TAG_P2PKH | TAG_P2SH => {
+                let network = match network {
+                    0 => NetworkKind::Main,
+                    1 => NetworkKind::Test,
+                    invalid => return Err(FromBytesError::UnknownNetwork(invalid)),
+                };
+                let hash: [u8; 20] =
+                    data.try_into().map_err(|_| FromBytesError::InvalidLength(bytes.len()))?;
+                if tag == TAG_P2PKH {
+                    AddressInner::P2pkh { hash: PubkeyHash::from_byte_array(hash), network }
+                } else {
+                    AddressInner::P2sh { hash: ScriptHash::from_byte_array(hash), network }
+                }
+            }
+            TAG_SEGWIT => {
+                let hrp = match network {
+                    0 => KnownHrp::Mainnet,
+                    1 => KnownHrp::Testnets,
+                    2 => KnownHrp::Regtest,
+                    invalid => return Err(FromBytesError::UnknownNetwork(invalid)),
+                };
+                let (&version, program) =
+                    data.split_first().ok_or(FromBytesError::InvalidLength(bytes.len()))?;
+                let version = WitnessVersion::try_from(version)?;
+                let program = WitnessProgram::new(version, program)?;
+                AddressInner::Segwit { program, hrp }
+            }
+            invalid => return Err(FromBytesError::UnknownType(invalid)),
+        };
+        Ok(Address(inner, PhantomData))
+    }
+
     /// Returns a reference to the checked address.
     ///
     /// This function is dangerous in case the address is not a valid checked address.
@@ -733,6 +1174,16 @@ impl Address<NetworkUnchecked> {
         }
     }
 
+    /// Checks whether this address is valid for the network described by `params`.
+    ///
+    /// Currently this is the same as calling [`Address::is_valid_for_network`] with
+    /// `params.network`, in particular testnet and signet addresses are indistinguishable.
+    /// Applications that carry consensus parameters around should prefer this function since it
+    /// leaves room for validating against parameters of custom networks (e.g. a custom signet).
+    pub fn is_valid_for_params(&self, params: impl AsRef<Params>) -> bool {
+        self.is_valid_for_network(params.as_ref().network)
+    }
+
     /// Checks whether network of this address is as required.
     ///
     /// For details about this mechanism, see section [*Parsing addresses*](Address#parsing-addresses)
@@ -784,6 +1235,18 @@ impl Address<NetworkUnchecked> {
         }
     }
 
+    /// Checks whether the address is valid on any of the `required` networks, and marks it as
+    /// checked if so.
+    ///
+    /// This is useful when several networks are acceptable, e.g. any of the test networks.
+    pub fn require_network_in(self, required: &[Network]) -> Result<Address, ParseError> {
+        if required.iter().any(|&network| self.is_valid_for_network(network)) {
+            Ok(self.assume_checked())
+        } else {
+            Err(NetworkSetValidationError { required: required.to_vec(), address: self }.into())
+        }
+    }
+
     /// Marks, without any additional checks, network of this address as checked.
     ///
     /// Improper use of this method may lead to loss of funds. Reader will most likely prefer
@@ -803,12 +1266,82 @@ impl Address<NetworkUnchecked> {
     }
 }
 
+/// Checks that every address in `addresses` is valid for `required` network.
+///
+/// On success returns the network checked addresses in the same order they were yielded. On
+/// failure returns the index of the first address that is not valid for `required`, along with
+/// the error returned by [`Address::require_network`].
+pub fn require_network_all<I>(
+    addresses: I,
+    required: Network,
+) -> Result<Vec<Address>, (usize, ParseError)>
+where
+    I: Iterator<Item = Address<NetworkUnchecked>>,
+{
+    addresses
+        .enumerate()
+        .map(|(i, address)| address.require_network(required).map_err(|e| (i, e)))
+        .collect()
+}
+
+/// An index from script pubkeys to the addresses they pay to.
+///
+/// Used when scanning blocks for outputs paying to a watch list of addresses, each output's
+/// script pubkey can be looked up without deriving it for every address.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptPubkeyIndex {
+    map: HashMap<ScriptBuf, Address>,
+}
+
+#[cfg(feature = "std")]
+impl ScriptPubkeyIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self { Self::default() }
+
+    /// Adds `address` to the index.
+    ///
+    /// Returns the previously indexed address with the same script pubkey, if any. This may
+    /// differ from `address` if they are for different networks.
+    pub fn insert(&mut self, address: Address) -> Option<Address> {
+        self.map.insert(address.script_pubkey(), address)
+    }
+
+    /// Returns the address paid to by `script`, if it is in the index.
+    pub fn lookup(&self, script: &Script) -> Option<&Address> { self.map.get(script) }
+
+    /// Returns the number of indexed script pubkeys.
+    pub fn len(&self) -> usize { self.map.len() }
+
+    /// Returns true if the index is empty.
+    pub fn is_empty(&self) -> bool { self.map.is_empty() }
+}
+
+#[cfg(feature = "std")]
+impl FromIterator<Address> for ScriptPubkeyIndex {
+    fn from_iter<I: IntoIterator<Item = Address>>(iter: I) -> Self {
+        let mut index = ScriptPubkeyIndex::new();
+        index.extend(iter);
+        index
+    }
+}
+
+#[cfg(feature = "std")]
+impl Extend<Address> for ScriptPubkeyIndex {
+    fn extend<I: IntoIterator<Item = Address>>(&mut self, iter: I) {
+        for address in iter {
+            self.insert(address);
+        }
+    }
+}
+
 impl From<Address> for script::ScriptBuf {
     fn from(a: Address) -> Self { a.script_pubkey() }
 }
 
 // Alternate formatting `{:#}` is used to return uppercase version of bech32 addresses which should
-// be used in QR codes, see [`Address::to_qr_uri`].
+// be used in QR codes, see [`Address::to_qr_uri`]. Normal formatting (and so `to_string`) always
+// produces lowercase bech32, regardless of the case of the string the address was parsed from.
 impl fmt::Display for Address {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result { fmt::Display::fmt(&self.0, fmt) }
 }
@@ -830,17 +1363,25 @@ impl FromStr for Address<NetworkUnchecked> {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Address<NetworkUnchecked>, ParseError> {
-        if let Ok((hrp, witness_version, data)) = bech32::segwit::decode(s) {
-            let version = WitnessVersion::try_from(witness_version.to_u8())?;
-            let program = WitnessProgram::new(version, &data)
-                .expect("bech32 guarantees valid program length for witness");
-
-            let hrp = KnownHrp::from_hrp(hrp)?;
-            let inner = AddressInner::Segwit { program, hrp };
-            return Ok(Address(inner, PhantomData));
+        if s.trim().is_empty() {
+            return Err(ParseError::Empty);
         }
 
-        // If segwit decoding fails, assume its a legacy address.
+        match bech32::segwit::decode(s) {
+            Ok((hrp, witness_version, data)) => {
+                let version = WitnessVersion::try_from(witness_version.to_u8())?;
+                let program = WitnessProgram::new(version, &data)
+                    .expect("bech32 guarantees valid program length for witness");
+
+                let hrp = KnownHrp::from_hrp(hrp)?;
+                let inner = AddressInner::Segwit { program, hrp };
+                return Ok(Address(inner, PhantomData));
+            }
+            // No base58 address starts with a segwit prefix, report the bech32 error.
+            Err(e) if has_segwit_prefix(s) => return Err(e.into()),
+            // If segwit decoding fails, assume its a legacy address.
+            Err(_) => {}
+        }
 
         if s.len() > 50 {
             return Err(LegacyAddressTooLongError { length: s.len() }.into());
@@ -877,6 +1418,13 @@ impl FromStr for Address<NetworkUnchecked> {
     }
 }
 
+/// Returns true if `s` starts, case-insensitively, with the bech32 prefix of a known network.
+fn has_segwit_prefix(s: &str) -> bool {
+    ["bc1", "tb1", "bcrt1"]
+        .iter()
+        .any(|prefix| s.get(..prefix.len()).map_or(false, |p| p.eq_ignore_ascii_case(prefix)))
+}
+
 /// Convert a byte array of a pubkey hash into a segwit redeem hash
 fn segwit_redeem_hash(pubkey_hash: PubkeyHash) -> crate::hashes::hash160::Hash {
     let mut sha_engine = sha256::Hash::engine();
@@ -891,7 +1439,7 @@ mod tests {
 
     use super::*;
     use crate::consensus::params;
-    use crate::network::Network::{Bitcoin, Testnet};
+    use crate::network::Network::{Bitcoin, Regtest, Signet, Testnet};
 
     fn roundtrips(addr: &Address, network: Network) {
         assert_eq!(
@@ -966,6 +1514,7 @@ mod tests {
         let addr = Address::p2sh(&script, NetworkKind::Test).unwrap();
         assert_eq!(&addr.to_string(), "2N3zXjbwdTcPsJiy8sUK9FhWJhqQCxA8Jjr");
         assert_eq!(addr.address_type(), Some(AddressType::P2sh));
+        assert_eq!(addr.script_hash(), Some(script.script_hash().unwrap()));
         roundtrips(&addr, Testnet);
     }
 
@@ -988,6 +1537,9 @@ mod tests {
         assert_eq!(&addr.to_string(), "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw");
         assert_eq!(addr.address_type(), Some(AddressType::P2wpkh));
         roundtrips(&addr, Bitcoin);
+
+        // A secp256k1 key is always compressed.
+        assert_eq!(Address::p2wpkh_from_secp_pubkey(key.0, KnownHrp::Mainnet), addr);
     }
 
     #[test]
@@ -1013,6 +1565,12 @@ mod tests {
         assert_eq!(&addr.to_string(), "3QBRmWNqqBGme9er7fMkGqtZtp4gjMFxhE");
         assert_eq!(addr.address_type(), Some(AddressType::P2sh));
         roundtrips(&addr, Bitcoin);
+
+        assert_eq!(Address::p2shwpkh_from_secp_pubkey(key.0, NetworkKind::Main), addr);
+
+        let redeem_script = Address::p2shwpkh_redeem_script(key);
+        assert_eq!(redeem_script.to_hex_string(), "0014177a9ed6d4082868f17ed0cf66364ad8d95ffc4d");
+        assert_eq!(addr.script_hash(), Some(redeem_script.script_hash().unwrap()));
     }
 
     #[test]
@@ -1087,6 +1645,10 @@ mod tests {
                 .require_network(Network::Bitcoin)
                 .expect("mainnet");
             assert_eq!(&addr.address_type(), expected_type);
+
+            let from_script =
+                Address::from_script(&addr.script_pubkey(), Network::Bitcoin).unwrap();
+            assert_eq!(&from_script.address_type(), expected_type);
         }
     }
 
@@ -1189,6 +1751,22 @@ mod tests {
         {
             let addr = Address::from_str(el).unwrap().assume_checked();
             assert_eq!(addr.to_qr_uri(), format!("bitcoin:{}", el.to_ascii_uppercase()));
+
+            let upper = Address::from_str(&el.to_ascii_uppercase()).unwrap().assume_checked();
+            assert_eq!(upper.to_string(), *el);
+        }
+    }
+
+    #[test]
+    fn write_qr_uri() {
+        for el in [
+            "132F25rTsvBdp9JzLLBHP5mvGY66i1xdiM",
+            "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej",
+        ] {
+            let addr = Address::from_str(el).unwrap().assume_checked();
+            let mut uri = String::new();
+            addr.write_qr_uri(&mut uri).unwrap();
+            assert_eq!(uri, addr.to_qr_uri());
         }
     }
 
@@ -1394,4 +1972,547 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn electrum_scripthash() {
+        // Example from the Electrum protocol docs.
+        let addr =
+            Address::from_str("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa").unwrap().assume_checked();
+        let hash = addr.electrum_scripthash();
+        assert_eq!(
+            hash.to_string(),
+            "6191c3b590bfcfa0475e877c302da1e323497acf3b42c08d8fa28e364edf018b"
+        );
+
+        let mut reversed = hash.to_byte_array();
+        reversed.reverse();
+        assert_eq!(
+            reversed,
+            hex!("8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161")
+        );
+        assert_eq!(
+            addr.electrum_scripthash_hex(),
+            "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161"
+        );
+    }
+
+    #[test]
+    fn script_pubkey_len() {
+        let addresses = [
+            "1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY",
+            "33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k",
+            "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw",
+            "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej",
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+        ];
+        for s in addresses {
+            let addr = s.parse::<Address<_>>().unwrap().assume_checked();
+            assert_eq!(addr.script_pubkey_len(), addr.script_pubkey().len(), "address: {}", s);
+        }
+    }
+
+    #[test]
+    fn matches_script_pubkey_checks_witness_version() {
+        let program = [0x75; 20];
+        let v0 = WitnessProgram::new(WitnessVersion::V0, &program).unwrap();
+        let v0 = Address::from_witness_program(v0, Bitcoin);
+        let v2 = WitnessProgram::new(WitnessVersion::V2, &program).unwrap();
+        let v2 = Address::from_witness_program(v2, Bitcoin);
+
+        assert!(v0.matches_script_pubkey(&v0.script_pubkey()));
+        assert!(!v0.matches_script_pubkey(&v2.script_pubkey()));
+        assert!(!v2.matches_script_pubkey(&v0.script_pubkey()));
+    }
+
+    #[test]
+    fn parse_with_network() {
+        let addr = Address::parse("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw", Network::Bitcoin)
+            .expect("valid mainnet address");
+        assert_eq!(addr.address_type(), Some(AddressType::P2wpkh));
+        assert_eq!(addr.to_string(), "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw");
+
+        let addr = Address::parse("2N83imGV3gPwBzKJQvWJ7cRUY2SpUyU6A5e", Network::Signet)
+            .expect("valid signet address");
+        assert_eq!(addr.address_type(), Some(AddressType::P2sh));
+    }
+
+    #[test]
+    fn require_network_all_reports_first_failure() {
+        let addresses = [
+            "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw",
+            "32iVBEu4dxkUQk9dJbZUiBiQdmypcEyJRf",
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+            "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+        ];
+        let unchecked = || addresses.iter().map(|s| s.parse::<Address<_>>().unwrap());
+
+        let (index, err) = require_network_all(unchecked(), Bitcoin).unwrap_err();
+        assert_eq!(index, 2);
+        assert!(matches!(err, ParseError::NetworkValidation(_)));
+
+        let valid = require_network_all(unchecked().take(2), Bitcoin).unwrap();
+        assert_eq!(valid.len(), 2);
+        assert_eq!(valid[0].to_string(), addresses[0]);
+        assert_eq!(valid[1].to_string(), addresses[1]);
+    }
+
+    #[test]
+    fn require_network_in() {
+        let s = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+        let unchecked = s.parse::<Address<_>>().unwrap();
+
+        let addr = unchecked.clone().require_network_in(&[Bitcoin, Signet]).unwrap();
+        assert_eq!(addr.to_string(), s);
+        assert!(unchecked.clone().require_network_in(&[Testnet]).is_ok());
+
+        let err = unchecked.clone().require_network_in(&[Bitcoin, Regtest]).unwrap_err();
+        assert_eq!(err.encoding(), Encoding::Bech32);
+        match err {
+            ParseError::NetworkSetValidation(ref e) => {
+                assert_eq!(e.required(), [Bitcoin, Regtest]);
+                assert_eq!(
+                    e.to_string(),
+                    format!("address {} is not valid on any of [bitcoin, regtest]", s)
+                );
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        assert!(unchecked.require_network_in(&[]).is_err());
+    }
+
+    #[test]
+    fn parse_lenient() {
+        let want = "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw";
+        for s in [
+            "  bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw\t\n",
+            "bitcoin:BC1QVZVKJN4Q3NSZQXRV3NRAGA2R822XJTY3YKVKUW",
+            " BITCOIN:bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw ",
+            "Bc1qVzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw",
+        ] {
+            let addr = Address::parse_lenient(s, Bitcoin).expect(s);
+            assert_eq!(addr.to_string(), want);
+        }
+
+        // Base58 is case sensitive.
+        let addr = Address::parse_lenient(" 1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY ", Bitcoin).unwrap();
+        assert_eq!(addr.to_string(), "1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY");
+        assert!(Address::parse_lenient("1qjvdzdqb1vpbdk7udeyvxy9mr27cjiyhy", Bitcoin).is_err());
+
+        assert!(Address::parse_lenient(want, Testnet).is_err());
+    }
+
+    #[test]
+    fn regtest_hrp() {
+        let addr: Address<_> = "bcrt1q2nfxmhd4n3c8834pj72xagvyr9gl57n5r94fsl".parse().unwrap();
+        assert!(addr.known_hrp().unwrap().is_regtest());
+        assert!(addr.is_valid_for_network(Network::Regtest));
+
+        let addr: Address<_> = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx".parse().unwrap();
+        assert_eq!(addr.known_hrp(), Some(KnownHrp::Testnets));
+        assert!(!addr.known_hrp().unwrap().is_regtest());
+
+        let addr: Address<_> = "mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn".parse().unwrap();
+        assert_eq!(addr.known_hrp(), None);
+    }
+
+    #[test]
+    fn valid_for_params() {
+        let addr: Address<_> = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx".parse().unwrap();
+        assert!(addr.is_valid_for_params(&params::TESTNET));
+        assert!(addr.is_valid_for_params(&params::SIGNET));
+        assert!(!addr.is_valid_for_params(&params::MAINNET));
+        assert!(!addr.is_valid_for_params(&params::REGTEST));
+
+        let addr: Address<_> = "2N83imGV3gPwBzKJQvWJ7cRUY2SpUyU6A5e".parse().unwrap();
+        assert!(addr.is_valid_for_params(&params::TESTNET));
+        assert!(addr.is_valid_for_params(&params::SIGNET));
+        assert!(addr.is_valid_for_params(Network::Regtest));
+        assert!(!addr.is_valid_for_params(&params::MAINNET));
+    }
+
+    #[test]
+    fn parse_with_wrong_network() {
+        let s = "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw";
+        let unchecked = Address::from_str(s).unwrap();
+        let want = NetworkValidationError { required: Testnet, address: unchecked };
+
+        assert_eq!(Address::parse(s, Testnet), Err(ParseError::NetworkValidation(want)));
+    }
+
+    #[test]
+    fn bech32_parts() {
+        let addr = Address::from_str("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw")
+            .unwrap()
+            .assume_checked();
+        let (hrp, version, program) = addr.bech32_parts().unwrap();
+        assert_eq!(hrp, bech32::hrp::BC);
+        assert_eq!(version, WitnessVersion::V0);
+        assert_eq!(program, hex!("6099694ea08ce020186c8cc7d475433a94692c91"));
+        assert_eq!(program, &addr.script_pubkey().as_bytes()[2..]);
+
+        let addr =
+            Address::from_str("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY").unwrap().assume_checked();
+        assert_eq!(addr.bech32_parts(), None);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let addresses = [
+            "1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY",
+            "mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn",
+            "33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k",
+            "2N83imGV3gPwBzKJQvWJ7cRUY2SpUyU6A5e",
+            "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw",
+            "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej",
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+            "bcrt1q2nfxmhd4n3c8834pj72xagvyr9gl57n5r94fsl",
+            "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+        ];
+        for s in addresses {
+            let addr = Address::from_str(s).unwrap();
+            let bytes = addr.to_bytes();
+            assert_eq!(Address::from_bytes(&bytes).unwrap(), addr, "{}", s);
+        }
+
+        let addr = Address::from_str("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY").unwrap();
+        let bytes = addr.to_bytes();
+        assert_eq!(bytes[..2], [0, 0]);
+        assert_eq!(bytes.len(), 22);
+        let addr = Address::from_str("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw").unwrap();
+        assert_eq!(addr.to_bytes()[..3], [2, 0, 0]);
+    }
+
+    #[test]
+    fn from_bytes_invalid() {
+        assert_eq!(Address::from_bytes(&[]), Err(FromBytesError::InvalidLength(0)));
+        assert_eq!(Address::from_bytes(&[0]), Err(FromBytesError::InvalidLength(1)));
+        assert_eq!(Address::from_bytes(&[0, 0, 1, 2]), Err(FromBytesError::InvalidLength(4)));
+        assert_eq!(Address::from_bytes(&[3, 0]), Err(FromBytesError::UnknownType(3)));
+        assert_eq!(Address::from_bytes(&[1, 2]), Err(FromBytesError::UnknownNetwork(2)));
+        assert_eq!(Address::from_bytes(&[2, 3]), Err(FromBytesError::UnknownNetwork(3)));
+        assert_eq!(Address::from_bytes(&[2, 0]), Err(FromBytesError::InvalidLength(2)));
+        assert!(matches!(
+            Address::from_bytes(&[2, 0, 17, 0, 0]),
+            Err(FromBytesError::WitnessVersion(_))
+        ));
+        assert!(matches!(
+            Address::from_bytes(&[2, 0, 0, 0, 0]),
+            Err(FromBytesError::WitnessProgram(_))
+        ));
+    }
+
+    #[test]
+    fn same_type_as() {
+        let parse = |s: &str| Address::from_str(s).unwrap().assume_checked();
+
+        let p2wpkh = parse("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw");
+        let other_p2wpkh = parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+        let p2wsh = parse("bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej");
+        let p2tr = parse("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr");
+        let p2pkh = parse("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY");
+
+        assert!(p2wpkh.same_type_as(&other_p2wpkh));
+        assert!(p2wpkh.same_type_as(&p2wpkh));
+        assert!(!p2wpkh.same_type_as(&p2tr));
+        assert!(!p2wpkh.same_type_as(&p2wsh));
+        assert!(!p2wpkh.same_type_as(&p2pkh));
+
+        // Future segwit versions.
+        let v2 = parse("bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs");
+        assert!(v2.same_type_as(&v2));
+        assert!(!v2.same_type_as(&p2tr));
+        let v1_len_40 =
+            parse("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y");
+        assert!(!v1_len_40.same_type_as(&v2));
+        assert!(!v1_len_40.same_type_as(&p2tr));
+    }
+
+    #[test]
+    fn spending_scriptsig_template() {
+        let template = |s: &str| {
+            Address::from_str(s)
+                .unwrap()
+                .assume_checked()
+                .spending_scriptsig_template()
+                .map(|t| t.weight().to_wu())
+        };
+
+        // 1 + 107 byte scriptSig.
+        assert_eq!(template("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY"), Some(432));
+        // Empty scriptSig and two witness items: 1 + (1 + 72) + (1 + 33).
+        assert_eq!(template("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw"), Some(4 + 108));
+        // Empty scriptSig and a single 64 byte signature.
+        assert_eq!(
+            template("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"),
+            Some(4 + 66)
+        );
+
+        assert_eq!(template("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k"), None);
+        assert_eq!(
+            template("bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej"),
+            None
+        );
+        assert_eq!(template("bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs"), None);
+    }
+
+    #[test]
+    fn spending_weight() {
+        let weight = |s: &str| {
+            Address::from_str(s).unwrap().assume_checked().spending_weight().map(Weight::to_wu)
+        };
+
+        // 148 vbytes.
+        assert_eq!(weight("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY"), Some(592));
+        // 68 vbytes.
+        assert_eq!(weight("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw"), Some(272));
+        // 57.5 vbytes.
+        assert_eq!(
+            weight("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"),
+            Some(230)
+        );
+        assert_eq!(weight("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k"), None);
+    }
+
+    #[test]
+    fn address_type_spk_size() {
+        let addresses = [
+            ("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY", 25),
+            ("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k", 23),
+            ("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw", 22),
+            ("bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej", 34),
+            ("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr", 34),
+        ];
+        for (s, size) in addresses {
+            let addr = Address::from_str(s).unwrap().assume_checked();
+            let address_type = addr.address_type().unwrap();
+            assert_eq!(address_type.spk_size(), size, "{}", address_type);
+            assert_eq!(address_type.spk_size(), addr.script_pubkey().len());
+        }
+    }
+
+    #[test]
+    fn display_len() {
+        let addresses = [
+            "1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY",
+            "33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k",
+            "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw",
+            "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej",
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            "bcrt1q2nfxmhd4n3c8834pj72xagvyr9gl57n5r94fsl",
+            "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+        ];
+        for s in addresses {
+            let addr = Address::from_str(s).unwrap().assume_checked();
+            assert_eq!(addr.display_len(), addr.to_string().chars().count(), "{}", s);
+        }
+
+        // Base58 encodes leading zero bytes as '1' so the length varies.
+        let zero = Address::p2pkh(PubkeyHash::from_byte_array([0; 20]), NetworkKind::Main);
+        assert_eq!(zero.to_string(), "1111111111111111111114oLvT2");
+        assert_eq!(zero.display_len(), 27);
+    }
+
+    #[test]
+    fn from_witness_program_standard() {
+        // A v0 program of the wrong length can't even be constructed.
+        assert!(WitnessProgram::new(WitnessVersion::V0, &[0; 21]).is_err());
+
+        let program = WitnessProgram::new(WitnessVersion::V0, &[0; 20]).unwrap();
+        let addr = Address::from_witness_program_standard(program, KnownHrp::Mainnet).unwrap();
+        assert_eq!(addr, Address::from_witness_program(program, KnownHrp::Mainnet));
+        assert_eq!(addr.address_type(), Some(AddressType::P2wpkh));
+
+        let program = WitnessProgram::new(WitnessVersion::V1, &[0; 32]).unwrap();
+        assert!(Address::from_witness_program_standard(program, KnownHrp::Mainnet).is_ok());
+
+        let program = WitnessProgram::new(WitnessVersion::V1, &[0; 20]).unwrap();
+        let err = Address::from_witness_program_standard(program, KnownHrp::Mainnet).unwrap_err();
+        assert_eq!(err.witness_program(), program);
+
+        let program = WitnessProgram::new(WitnessVersion::V2, &[0; 16]).unwrap();
+        assert!(Address::from_witness_program_standard(program, KnownHrp::Mainnet).is_ok());
+    }
+
+    #[test]
+    fn same_script_pubkey() {
+        let parse = |s: &str| Address::from_str(s).unwrap().assume_checked();
+        let pk = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+            .parse::<PublicKey>()
+            .unwrap();
+        let cpk = CompressedPublicKey(pk.inner);
+
+        // Segwit, only the HRP differs.
+        let main = Address::p2wpkh(cpk, KnownHrp::Mainnet);
+        let test = Address::p2wpkh(cpk, KnownHrp::Testnets);
+        assert_ne!(main, test);
+        assert!(main.same_script_pubkey(&test));
+        assert_eq!(main.script_pubkey(), test.script_pubkey());
+
+        // Legacy, the network only changes the version byte of the address.
+        let main = Address::p2pkh(pk, NetworkKind::Main);
+        let test = Address::p2pkh(pk, NetworkKind::Test);
+        assert_ne!(main.to_string(), test.to_string());
+        assert!(main.same_script_pubkey(&test));
+        assert_eq!(main.script_pubkey(), test.script_pubkey());
+
+        // Same hash but different script types.
+        let p2sh = Address::p2sh_from_hash(
+            ScriptHash::from_byte_array(pk.pubkey_hash().to_byte_array()),
+            NetworkKind::Main,
+        );
+        assert!(!main.same_script_pubkey(&p2sh));
+        assert_ne!(main.script_pubkey(), p2sh.script_pubkey());
+
+        // BIP-173 test vector for the same key.
+        let p2wpkh = Address::p2wpkh(cpk, KnownHrp::Mainnet);
+        assert!(parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").same_script_pubkey(&p2wpkh));
+        assert!(!parse("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw").same_script_pubkey(&p2wpkh));
+    }
+
+    #[test]
+    fn segwit_version() {
+        let parse = |s: &str| s.parse::<Address<_>>().unwrap().assume_checked();
+
+        let p2wpkh = parse("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw");
+        assert_eq!(p2wpkh.segwit_version(), Some(WitnessVersion::V0));
+        let p2tr = parse("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr");
+        assert_eq!(p2tr.segwit_version(), Some(WitnessVersion::V1));
+        let p2pkh = parse("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY");
+        assert_eq!(p2pkh.segwit_version(), None);
+        let p2sh = parse("33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k");
+        assert_eq!(p2sh.segwit_version(), None);
+    }
+
+    #[test]
+    fn script_pubkey_into() {
+        let addresses = [
+            "1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY",
+            "33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k",
+            "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw",
+            "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej",
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+        ];
+
+        let mut buf = vec![0xff];
+        let mut want = vec![0xff];
+        for s in &addresses {
+            let addr = s.parse::<Address<_>>().unwrap().assume_checked();
+            let spk = addr.script_pubkey();
+            let start = buf.len();
+            addr.script_pubkey_into(&mut buf);
+            assert_eq!(&buf[start..], spk.as_bytes());
+            want.extend_from_slice(spk.as_bytes());
+        }
+        assert_eq!(buf, want);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn script_pubkey_index() {
+        let watch = [
+            "1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY",
+            "33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k",
+            "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw",
+        ]
+        .iter()
+        .map(|s| s.parse::<Address<_>>().unwrap().assume_checked())
+        .collect::<Vec<_>>();
+        let index = watch.iter().cloned().collect::<ScriptPubkeyIndex>();
+        assert_eq!(index.len(), 3);
+
+        let outputs = [
+            watch[2].script_pubkey(),
+            ScriptBuf::new_op_return([]),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+                .parse::<Address<_>>()
+                .unwrap()
+                .assume_checked()
+                .script_pubkey(),
+            watch[0].script_pubkey(),
+        ];
+        let matched = outputs.iter().filter_map(|spk| index.lookup(spk)).collect::<Vec<_>>();
+        assert_eq!(matched, [&watch[2], &watch[0]]);
+
+        // The same script pubkey on another network replaces the indexed address.
+        let mut index = index;
+        let testnet = Address::p2sh_from_hash(watch[1].script_hash().unwrap(), NetworkKind::Test);
+        assert_eq!(index.insert(testnet.clone()), Some(watch[1].clone()));
+        assert_eq!(index.lookup(&watch[1].script_pubkey()), Some(&testnet));
+        assert!(ScriptPubkeyIndex::new().is_empty());
+    }
+
+    #[test]
+    fn nested_segwit() {
+        let key = "026c468be64d22761c30cd2f12cbc7de255d592d7904b1bab07236897cc4c2e766"
+            .parse::<CompressedPublicKey>()
+            .unwrap();
+        let redeem_script = Address::p2shwpkh_redeem_script(key);
+        let addr = Address::p2shwpkh(key, NetworkKind::Main);
+        assert!(addr.could_be_nested_segwit());
+        assert!(addr.is_nested_segwit_for(&redeem_script));
+
+        // A P2SH address wrapping a non-segwit script looks the same from the address alone.
+        let legacy_script = ScriptBuf::new_p2pkh(key.pubkey_hash());
+        let p2sh = Address::p2sh(&legacy_script, NetworkKind::Main).unwrap();
+        assert!(p2sh.could_be_nested_segwit());
+        assert!(!p2sh.is_nested_segwit_for(&legacy_script));
+        assert!(!p2sh.is_nested_segwit_for(&redeem_script));
+
+        // Native segwit is not nested.
+        let p2wpkh = Address::p2wpkh(key, KnownHrp::Mainnet);
+        assert!(!p2wpkh.could_be_nested_segwit());
+        assert!(!p2wpkh.is_nested_segwit_for(&redeem_script));
+    }
+
+    #[test]
+    fn parse_unknown_hrp() {
+        // Valid bech32 segwit address with a litecoin hrp.
+        let want = ParseError::UnknownHrp(UnknownHrpError("ltc".to_owned()));
+        let s = "ltc1qw46h2at4w46h2at4w46h2at4w46h2at4w7ttue";
+        assert_eq!(Address::from_str(s), Err(want.clone()));
+        assert_eq!(Address::from_str(&s.to_uppercase()), Err(want));
+        assert_eq!(Address::from_str(s).unwrap_err().encoding(), Encoding::Bech32);
+
+        // Malformed bech32 is not reported as an unknown hrp.
+        let err = Address::from_str("ltc1qw46h2at4w46h2at4w46h2at4w46h2at4w7ttuq").unwrap_err();
+        assert!(!matches!(err, ParseError::UnknownHrp(_)));
+    }
+
+    #[test]
+    fn parse_empty() {
+        for s in ["", "   ", "\t\n"] {
+            assert_eq!(Address::from_str(s), Err(ParseError::Empty));
+            assert_eq!(Address::parse_lenient(s, Bitcoin), Err(ParseError::Empty));
+        }
+        assert_eq!(ParseError::Empty.to_string(), "empty address string");
+    }
+
+    #[test]
+    fn parse_error_encoding() {
+        // Bad checksum.
+        let err = Address::from_str("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuv").unwrap_err();
+        assert!(matches!(err, ParseError::Bech32(_)));
+        assert_eq!(err.encoding(), Encoding::Bech32);
+
+        let err = Address::from_str("BC1QVZVKJN4Q3NSZQXRV3NRAGA2R822XJTY3YKVKUV").unwrap_err();
+        assert_eq!(err.encoding(), Encoding::Bech32);
+
+        let err = Address::from_str("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhZ").unwrap_err();
+        assert!(matches!(err, ParseError::Base58(_)));
+        assert_eq!(err.encoding(), Encoding::Base58);
+
+        let err =
+            Address::parse("bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw", Testnet).unwrap_err();
+        assert_eq!(err.encoding(), Encoding::Bech32);
+
+        let err = Address::parse("1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY", Testnet).unwrap_err();
+        assert_eq!(err.encoding(), Encoding::Base58);
+
+        let err = Address::from_str("").unwrap_err();
+        assert_eq!(err.encoding(), Encoding::Unknown);
+        assert_eq!(ParseError::Empty.encoding(), Encoding::Unknown);
+    }
 }
@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: CC0-1.0
+
+//! Silent payment addresses as defined by [BIP352].
+//!
+//! A silent payment address encodes two public keys, the scan key and the spend key, using bech32m
+//! with the human-readable part `sp` (mainnet) or `tsp` (testnets). This module only implements
+//! parsing and formatting the address, not deriving outputs from it.
+//!
+//! [BIP352]: <https://github.com/bitcoin/bips/blob/master/bip-0352.mediawiki>
+
+use core::fmt;
+use core::str::FromStr;
+
+use bech32::primitives::decode::{CheckedHrpstring, CheckedHrpstringError};
+use bech32::primitives::hrp::Hrp;
+use bech32::primitives::iter::{ByteIterExt, Fe32IterExt};
+use bech32::{Bech32m, Fe32};
+use internals::write_err;
+use secp256k1::PublicKey;
+
+use crate::address::UnknownHrpError;
+use crate::network::NetworkKind;
+use crate::prelude::Vec;
+
+/// The human-readable part of a mainnet silent payment address.
+const HRP_MAIN: Hrp = Hrp::parse_unchecked("sp");
+
+/// The human-readable part of a testnet, signet or regtest silent payment address.
+const HRP_TEST: Hrp = Hrp::parse_unchecked("tsp");
+
+/// Length of the payload of a version 0 address (two compressed public keys).
+const PAYLOAD_LEN: usize = 66;
+
+/// The highest version, versions above this are invalid.
+const MAX_VERSION: u8 = 30;
+
+/// A silent payment address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SilentPaymentAddress {
+    version: u8,
+    scan_key: PublicKey,
+    spend_key: PublicKey,
+    /// Data following the keys in a future version address, kept so it can be displayed again.
+    extra: Vec<u8>,
+    network: NetworkKind,
+}
+
+impl SilentPaymentAddress {
+    /// Creates a version 0 silent payment address.
+    pub fn new(scan_key: PublicKey, spend_key: PublicKey, network: impl Into<NetworkKind>) -> Self {
+        SilentPaymentAddress {
+            version: 0,
+            scan_key,
+            spend_key,
+            extra: Vec::new(),
+            network: network.into(),
+        }
+    }
+
+    /// Returns the address version.
+    pub fn version(&self) -> u8 { self.version }
+
+    /// Returns the public key used by the receiver to scan for payments.
+    pub fn scan_key(&self) -> PublicKey { self.scan_key }
+
+    /// Returns the public key used by the receiver to spend payments.
+    pub fn spend_key(&self) -> PublicKey { self.spend_key }
+
+    /// Returns the kind of network this address is for.
+    pub fn network_kind(&self) -> NetworkKind { self.network }
+}
+
+impl fmt::Display for SilentPaymentAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hrp = match self.network {
+            NetworkKind::Main => HRP_MAIN,
+            NetworkKind::Test => HRP_TEST,
+        };
+        let version = Fe32::try_from(self.version).expect("version is at most 30");
+
+        let scan_key = self.scan_key.serialize();
+        let spend_key = self.spend_key.serialize();
+
+        let chars = scan_key
+            .iter()
+            .chain(spend_key.iter())
+            .chain(self.extra.iter())
+            .copied()
+            .bytes_to_fes()
+            .with_checksum::<Bech32m>(&hrp)
+            .with_witness_version(version)
+            .chars();
+        for c in chars {
+            fmt::Write::write_char(f, c)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SilentPaymentAddress {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let checked = CheckedHrpstring::new::<Bech32m>(s)?;
+
+        let hrp = checked.hrp();
+        let network = if hrp == HRP_MAIN {
+            NetworkKind::Main
+        } else if hrp == HRP_TEST {
+            NetworkKind::Test
+        } else {
+            return Err(UnknownHrpError(hrp.to_lowercase()).into());
+        };
+
+        // The version is not a segwit version, it can be any field element.
+        let mut fes = checked
+            .data_part_ascii_no_checksum()
+            .iter()
+            .map(|&b| Fe32::from_char(b.into()).expect("checked by CheckedHrpstring"));
+
+        let version = fes.next().ok_or(ParseError::MissingVersion)?.to_u8();
+        if version > MAX_VERSION {
+            return Err(ParseError::InvalidVersion(version));
+        }
+
+        let fes = fes.collect::<Vec<Fe32>>();
+        let mut payload = fes.iter().copied().fes_to_bytes().collect::<Vec<u8>>();
+        // Like segwit addresses, padding must be less than 5 bits and all zero. This makes the
+        // encoding unique so the address is displayed exactly as it was parsed.
+        let padding = fes.len() * 5 - payload.len() * 8;
+        let last = fes.last().map_or(0, |fe| fe.to_u8());
+        if padding >= 5 || last & ((1 << padding) - 1) != 0 {
+            return Err(ParseError::InvalidPadding);
+        }
+
+        // Future versions may append data to the payload, it is ignored for forward compatibility.
+        if payload.len() < PAYLOAD_LEN || (version == 0 && payload.len() != PAYLOAD_LEN) {
+            return Err(ParseError::InvalidLength(payload.len()));
+        }
+
+        let scan_key = PublicKey::from_slice(&payload[..33])?;
+        let spend_key = PublicKey::from_slice(&payload[33..PAYLOAD_LEN])?;
+        let extra = payload.split_off(PAYLOAD_LEN);
+
+        Ok(SilentPaymentAddress { version, scan_key, spend_key, extra, network })
+    }
+}
+
+/// Error parsing a silent payment address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// Bech32m decoding error.
+    Bech32(CheckedHrpstringError),
+    /// The human-readable part is not `sp` or `tsp`.
+    UnknownHrp(UnknownHrpError),
+    /// The data part is empty.
+    MissingVersion,
+    /// The version is not supported (version 31).
+    InvalidVersion(u8),
+    /// The payload is too short, or not exactly 66 bytes for a version 0 address.
+    InvalidLength(usize),
+    /// The padding of the payload is 5 bits or longer, or is not all zero.
+    InvalidPadding,
+    /// The scan or spend key is not a valid public key.
+    InvalidKey(secp256k1::Error),
+}
+
+internals::impl_from_infallible!(ParseError);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ParseError::*;
+
+        match *self {
+            Bech32(ref e) => write_err!(f, "bech32m decoding error"; e),
+            UnknownHrp(ref e) => write_err!(f, "invalid silent payment hrp"; e),
+            MissingVersion => f.write_str("silent payment address is missing the version"),
+            InvalidVersion(v) => write!(f, "invalid silent payment address version: {}", v),
+            InvalidLength(len) =>
+                write!(f, "invalid silent payment address payload length: {}", len),
+            InvalidPadding => f.write_str("invalid silent payment address payload padding"),
+            InvalidKey(ref e) => write_err!(f, "invalid silent payment public key"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use ParseError::*;
+
+        match *self {
+            Bech32(ref e) => Some(e),
+            UnknownHrp(ref e) => Some(e),
+            InvalidKey(ref e) => Some(e),
+            MissingVersion | InvalidVersion(_) | InvalidLength(_) | InvalidPadding => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for ParseError {
+    fn from(e: CheckedHrpstringError) -> Self { Self::Bech32(e) }
+}
+
+impl From<UnknownHrpError> for ParseError {
+    fn from(e: UnknownHrpError) -> Self { Self::UnknownHrp(e) }
+}
+
+impl From<secp256k1::Error> for ParseError {
+    fn from(e: secp256k1::Error) -> Self { Self::InvalidKey(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From the BIP352 test vectors.
+    const ADDRESS: &str = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+    const SCAN_KEY: &str = "0220bcfac5b99e04ad1a06ddfb016ee13582609d60b6291e98d01a9bc9a16c96d4";
+    const SPEND_KEY: &str = "025cc9856d6f8375350e123978daac200c260cb5b5ae83106cab90484dcd8fcf36";
+
+    #[test]
+    fn parse_bip352_address() {
+        let addr = SilentPaymentAddress::from_str(ADDRESS).unwrap();
+        assert_eq!(addr.version(), 0);
+        assert_eq!(addr.scan_key(), SCAN_KEY.parse::<PublicKey>().unwrap());
+        assert_eq!(addr.spend_key(), SPEND_KEY.parse::<PublicKey>().unwrap());
+        assert_eq!(addr.network_kind(), NetworkKind::Main);
+        assert_eq!(addr.to_string(), ADDRESS);
+
+        let upper = ADDRESS.to_uppercase();
+        assert_eq!(SilentPaymentAddress::from_str(&upper).unwrap(), addr);
+    }
+
+    #[test]
+    fn testnet_round_trip() {
+        let scan_key = SCAN_KEY.parse::<PublicKey>().unwrap();
+        let spend_key = SPEND_KEY.parse::<PublicKey>().unwrap();
+        let addr = SilentPaymentAddress::new(scan_key, spend_key, NetworkKind::Test);
+
+        let s = addr.to_string();
+        assert!(s.starts_with("tsp1q"));
+        assert_eq!(SilentPaymentAddress::from_str(&s).unwrap(), addr);
+    }
+
+    #[test]
+    fn invalid_addresses() {
+        // Segwit address.
+        let err = SilentPaymentAddress::from_str(
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+        )
+        .unwrap_err();
+        assert_eq!(err, ParseError::UnknownHrp(UnknownHrpError("bc".to_owned())));
+
+        // Bad checksum.
+        let mut s = ADDRESS.to_owned();
+        s.pop();
+        s.push('q');
+        assert!(matches!(SilentPaymentAddress::from_str(&s), Err(ParseError::Bech32(_))));
+
+        let scan_key = SCAN_KEY.parse::<PublicKey>().unwrap();
+        let encode = |version: u8, payload: &[u8]| -> String {
+            payload
+                .iter()
+                .copied()
+                .bytes_to_fes()
+                .with_checksum::<Bech32m>(&HRP_MAIN)
+                .with_witness_version(Fe32::try_from(version).unwrap())
+                .chars()
+                .collect()
+        };
+        let mut payload = [0u8; 67];
+        payload[..33].copy_from_slice(&scan_key.serialize());
+        payload[33..66].copy_from_slice(&scan_key.serialize());
+
+        // Version 0 must be exactly 66 bytes but future versions may be longer.
+        assert_eq!(
+            SilentPaymentAddress::from_str(&encode(0, &payload)),
+            Err(ParseError::InvalidLength(67))
+        );
+        let s = encode(1, &payload);
+        let addr = SilentPaymentAddress::from_str(&s).unwrap();
+        assert_eq!(addr.version(), 1);
+        assert_eq!(addr.spend_key(), scan_key);
+        // The extra data is kept when displaying the address.
+        assert_eq!(addr.to_string(), s);
+
+        assert_eq!(
+            SilentPaymentAddress::from_str(&encode(1, &payload[..65])),
+            Err(ParseError::InvalidLength(65))
+        );
+        assert_eq!(
+            SilentPaymentAddress::from_str(&encode(31, &payload[..66])),
+            Err(ParseError::InvalidVersion(31))
+        );
+
+        // 66 bytes encode to 106 field elements with 2 bits of padding.
+        let with_data = |data: &str| -> String {
+            let fes = data.chars().map(|c| Fe32::from_char(c).unwrap());
+            fes.with_checksum::<Bech32m>(&HRP_MAIN).chars().collect()
+        };
+        let s = encode(1, &payload[..66]);
+        let data = &s[3..s.len() - 6];
+        assert_eq!(SilentPaymentAddress::from_str(&with_data(data)).unwrap().to_string(), s);
+        // Another field element is only padding.
+        let padded = with_data(&format!("{}q", data));
+        assert_eq!(SilentPaymentAddress::from_str(&padded), Err(ParseError::InvalidPadding));
+        // The padding bits are not zero.
+        let last = Fe32::from_char(data.chars().last().unwrap()).unwrap().to_u8();
+        let nonzero = format!("{}{}", &data[..data.len() - 1], Fe32::try_from(last | 1).unwrap());
+        let nonzero = with_data(&nonzero);
+        assert_eq!(SilentPaymentAddress::from_str(&nonzero), Err(ParseError::InvalidPadding));
+
+        payload[33] = 0x05;
+        assert!(matches!(
+            SilentPaymentAddress::from_str(&encode(0, &payload[..66])),
+            Err(ParseError::InvalidKey(_))
+        ));
+    }
+}
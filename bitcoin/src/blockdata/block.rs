@@ -16,10 +16,12 @@ use super::Weight;
 use crate::consensus::{encode, Decodable, Encodable, Params};
 use crate::internal_macros::{impl_consensus_encoding, impl_hashencode};
 use crate::merkle_tree::{MerkleNode as _, TxMerkleNode, WitnessMerkleNode};
+use crate::opcodes::{Class, ClassifyContext};
 use crate::pow::{CompactTarget, Target, Work};
 use crate::prelude::Vec;
+use crate::script::{self, Script};
 use crate::transaction::{Transaction, Wtxid};
-use crate::{script, VarInt};
+use crate::VarInt;
 
 hashes::hash_newtype! {
     /// A bitcoin block hash.
@@ -33,7 +35,10 @@ impl BlockHash {
     ///
     /// This is not the hash of a real block. It is used as the previous blockhash
     /// of the genesis block and in other placeholder contexts.
-    pub fn all_zeros() -> Self { Self::from_byte_array([0; 32]) }
+    pub const fn all_zeros() -> Self { Self::from_byte_array([0; 32]) }
+
+    /// Returns true if this is the "all zeros" blockhash.
+    pub fn is_all_zeros(&self) -> bool { *self == Self::all_zeros() }
 }
 
 /// Bitcoin block header.
@@ -353,6 +358,9 @@ impl Block {
     pub fn coinbase(&self) -> Option<&Transaction> { self.txdata.first() }
 
     /// Returns the block height, as encoded in the coinbase transaction according to BIP34.
+    ///
+    /// Heights 1 to 16 encoded with `OP_PUSHNUM_N`, as done by Bitcoin Core, are accepted. Before
+    /// version 0.32.0 these returned [`Bip34Error::NotPresent`].
     pub fn bip34_block_height(&self) -> Result<u64, Bip34Error> {
         // Citing the spec:
         // Add height as the first item in the coinbase transaction's scriptSig,
@@ -369,21 +377,31 @@ impl Block {
 
         let cb = self.coinbase().ok_or(Bip34Error::NotPresent)?;
         let input = cb.input.first().ok_or(Bip34Error::NotPresent)?;
-        let push = input.script_sig.instructions_minimal().next().ok_or(Bip34Error::NotPresent)?;
-        match push.map_err(|_| Bip34Error::NotPresent)? {
-            script::Instruction::PushBytes(b) => {
-                // Check that the number is encoded in the minimal way.
-                let h = b
-                    .read_scriptint()
-                    .map_err(|_e| Bip34Error::UnexpectedPush(b.as_bytes().to_vec()))?;
-                if h < 0 {
-                    Err(Bip34Error::NegativeHeight)
-                } else {
-                    Ok(h as u64)
-                }
+        bip34_height_from_script_sig(&input.script_sig)
+    }
+}
+
+/// Parses the BIP34 block height from the script sig of a coinbase input.
+pub(crate) fn bip34_height_from_script_sig(script_sig: &Script) -> Result<u64, Bip34Error> {
+    let push = script_sig.instructions_minimal().next().ok_or(Bip34Error::NotPresent)?;
+    match push.map_err(|_| Bip34Error::NotPresent)? {
+        script::Instruction::PushBytes(b) => {
+            // Check that the number is encoded in the minimal way.
+            let h = b
+                .read_scriptint()
+                .map_err(|_e| Bip34Error::UnexpectedPush(b.as_bytes().to_vec()))?;
+            if h < 0 {
+                Err(Bip34Error::NegativeHeight)
+            } else {
+                Ok(h as u64)
             }
-            _ => Err(Bip34Error::NotPresent),
         }
+        // Heights 1 to 16 are pushed using `OP_PUSHNUM_N` by Bitcoin Core.
+        script::Instruction::Op(op) => match op.classify(ClassifyContext::Legacy) {
+            Class::PushNum(h) if h < 0 => Err(Bip34Error::NegativeHeight),
+            Class::PushNum(h) => Ok(h as u64),
+            _ => Err(Bip34Error::NotPresent),
+        },
     }
 }
 
@@ -506,6 +524,13 @@ mod tests {
         assert_eq!(bad.bip34_block_height(), Err(super::Bip34Error::UnexpectedPush(push)));
     }
 
+    #[test]
+    fn blockhash_all_zeros() {
+        let genesis = crate::constants::genesis_block(&Params::new(Network::Bitcoin));
+        assert!(genesis.header.prev_blockhash.is_all_zeros());
+        assert!(!genesis.block_hash().is_all_zeros());
+    }
+
     #[test]
     fn block_test() {
         let params = Params::new(Network::Bitcoin);
@@ -71,6 +71,23 @@ macro_rules! all_opcodes {
                 }
             }
         }
+
+        impl Opcode {
+            /// Looks up an opcode by its name, also accepts the `OP_0`, `OP_FALSE`, `OP_TRUE`,
+            /// `OP_NOP2` and `OP_NOP3` aliases.
+            pub(crate) fn from_name(name: &str) -> Option<Opcode> {
+                match name {
+                    "OP_0" | "OP_FALSE" => Some(OP_PUSHBYTES_0),
+                    "OP_TRUE" => Some(OP_PUSHNUM_1),
+                    "OP_NOP2" => Some(OP_CLTV),
+                    "OP_NOP3" => Some(OP_CSV),
+                    $(
+                        stringify!($op) => Some($op),
+                    )+
+                    _ => None,
+                }
+            }
+        }
     }
 }
 
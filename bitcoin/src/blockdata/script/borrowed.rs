@@ -8,18 +8,22 @@ use core::ops::{
 use secp256k1::{Secp256k1, Verification};
 
 use super::PushBytes;
+use crate::address::Address;
 use crate::consensus::Encodable;
-use crate::key::{PublicKey, UntweakedPublicKey, WPubkeyHash};
+use crate::key::{PubkeyHash, PublicKey, UntweakedPublicKey, WPubkeyHash, XOnlyPublicKey};
+use crate::network::NetworkKind;
 use crate::opcodes::all::*;
 use crate::opcodes::{self, Opcode};
 use crate::policy::DUST_RELAY_TX_FEE;
 use crate::prelude::{Box, DisplayHex, sink, String, ToOwned, Vec};
+use crate::script::witness_program::{self, WitnessProgram};
 use crate::script::witness_version::WitnessVersion;
 use crate::script::{
-    bytes_to_asm_fmt, Builder, Instruction, InstructionIndices, Instructions,
+    bytes_to_asm_fmt, fmt_core_asm, Builder, Error, Instruction, InstructionIndices, Instructions,
     RedeemScriptSizeError, ScriptBuf, ScriptHash, WScriptHash, WitnessScriptSizeError,
 };
 use crate::taproot::{LeafVersion, TapLeafHash, TapNodeHash};
+use crate::witness::Witness;
 use crate::FeeRate;
 
 /// Bitcoin script slice.
@@ -156,6 +160,15 @@ impl Script {
         self.wscript_hash().map(ScriptBuf::new_p2wsh)
     }
 
+    /// Computes the P2WSH witness program corresponding to this witnessScript.
+    ///
+    /// This is equivalent to [`WitnessProgram::p2wsh`]. A P2WPKH program commits to a public key
+    /// rather than a script, use [`WitnessProgram::p2wpkh`] to create one.
+    #[inline]
+    pub fn to_p2wsh_program(&self) -> Result<WitnessProgram, WitnessScriptSizeError> {
+        WitnessProgram::p2wsh(self)
+    }
+
     /// Computes P2TR output with a given internal key and a single script spending path equal to
     /// the current script, assuming that the script is a Tapscript.
     #[inline]
@@ -180,6 +193,7 @@ impl Script {
     /// > special meaning. The value of the first push is called the "version byte". The following
     /// > byte vector pushed is called the "witness program".
     #[inline]
+    #[doc(alias = "witness_program_version")]
     pub fn witness_version(&self) -> Option<WitnessVersion> {
         let script_len = self.0.len();
         if !(4..=42).contains(&script_len) {
@@ -220,6 +234,28 @@ impl Script {
             && self.0[24] == OP_CHECKSIG.to_u8()
     }
 
+    /// Returns the public key hash if this script is a P2PKH output.
+    #[inline]
+    pub fn extract_p2pkh_hash(&self) -> Option<PubkeyHash> {
+        if self.is_p2pkh() {
+            let bytes = self.0[3..23].try_into().expect("length checked in is_p2pkh()");
+            Some(PubkeyHash::from_byte_array(bytes))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the script hash if this script is a P2SH output.
+    #[inline]
+    pub fn extract_p2sh_hash(&self) -> Option<ScriptHash> {
+        if self.is_p2sh() {
+            let bytes = self.0[2..22].try_into().expect("length checked in is_p2sh()");
+            Some(ScriptHash::from_byte_array(bytes))
+        } else {
+            None
+        }
+    }
+
     /// Checks whether a script is push only.
     ///
     /// Note: `OP_RESERVED` (`0x50`) and all the OP_PUSHNUM operations
@@ -239,6 +275,55 @@ impl Script {
         true
     }
 
+    /// Checks whether the opcodes of this script start with `opcodes`.
+    ///
+    /// Data pushes (including `OP_0`) are skipped, only the remaining opcodes are compared. Returns
+    /// false if the script fails to parse before the end of the pattern.
+    ///
+    /// ```
+    /// # use bitcoin::{PubkeyHash, ScriptBuf};
+    /// # use bitcoin::hashes::Hash;
+    /// use bitcoin::opcodes::all::*;
+    ///
+    /// let script = ScriptBuf::new_p2pkh(PubkeyHash::from_byte_array([0; 20]));
+    /// assert!(script.starts_with_opcodes(&[OP_DUP, OP_HASH160, OP_EQUALVERIFY]));
+    /// ```
+    pub fn starts_with_opcodes(&self, opcodes: &[Opcode]) -> bool {
+        let mut ops = self.opcodes();
+        opcodes.iter().all(|want| matches!(ops.next(), Some(Ok(op)) if op == *want))
+    }
+
+    /// Checks whether the opcodes of this script end with `opcodes`.
+    ///
+    /// Data pushes (including `OP_0`) are skipped, only the remaining opcodes are compared. Returns
+    /// false if the script fails to parse.
+    pub fn ends_with_opcodes(&self, opcodes: &[Opcode]) -> bool {
+        match self.opcodes().collect::<Result<Vec<_>, _>>() {
+            Ok(ops) => ops.ends_with(opcodes),
+            Err(_) => false,
+        }
+    }
+
+    /// Iterates over the opcodes of this script, skipping data pushes.
+    fn opcodes(&self) -> impl Iterator<Item = Result<Opcode, Error>> + '_ {
+        self.instructions().filter_map(|instruction| match instruction {
+            Ok(Instruction::Op(op)) => Some(Ok(op)),
+            Ok(Instruction::PushBytes(_)) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Returns the redeem script if this is a P2SH script sig.
+    ///
+    /// The redeem script is the last push of a push-only script sig. This does not check that the
+    /// redeem script hashes to the script hash of the output being spent.
+    pub fn p2sh_redeem_script(&self) -> Option<&Script> {
+        if !self.is_push_only() {
+            return None;
+        }
+        self.last_pushdata().map(|bytes| Script::from_bytes(bytes.as_bytes()))
+    }
+
     /// Checks whether a script pubkey is a P2PK output.
     ///
     /// You can obtain the public key, if its valid,
@@ -321,10 +406,59 @@ impl Script {
         instructions.next().is_none()
     }
 
+    /// Parses a bare multisig script pubkey, returning the number of required signatures and the
+    /// public keys.
+    ///
+    /// Returns `None` if the script is not of the form described in
+    /// [`is_multisig`](Self::is_multisig) or if any of the keys is not a valid public key.
+    pub fn parse_multisig(&self) -> Option<(usize, Vec<PublicKey>)> {
+        let mut instructions = self.instructions();
+        let required_sigs = match instructions.next() {
+            Some(Ok(Instruction::Op(op))) => op.decode_pushnum()?,
+            _ => return None,
+        };
+
+        let mut keys = Vec::new();
+        let num_pubkeys = loop {
+            match instructions.next()?.ok()? {
+                Instruction::PushBytes(bytes) =>
+                    keys.push(PublicKey::from_slice(bytes.as_bytes()).ok()?),
+                Instruction::Op(op) => break op.decode_pushnum()?,
+            }
+        };
+
+        if usize::from(num_pubkeys) != keys.len() || required_sigs > num_pubkeys {
+            return None;
+        }
+
+        match instructions.next() {
+            Some(Ok(Instruction::Op(op))) if op == OP_CHECKMULTISIG => {}
+            _ => return None,
+        }
+
+        if instructions.next().is_some() {
+            return None;
+        }
+
+        Some((required_sigs.into(), keys))
+    }
+
     /// Checks whether a script pubkey is a Segregated Witness (segwit) program.
+    ///
+    /// Use [`witness_version()`](Self::witness_version) instead if the version is needed too, it
+    /// does the same check.
     #[inline]
     pub fn is_witness_program(&self) -> bool { self.witness_version().is_some() }
 
+    /// Returns the witness program bytes if this script is a witness program.
+    ///
+    /// The returned bytes exclude the version and push opcodes, use
+    /// [`witness_version()`](Self::witness_version) to get the version.
+    #[inline]
+    pub fn witness_program_bytes(&self) -> Option<&[u8]> {
+        self.witness_version().map(|_| &self.0[2..])
+    }
+
     /// Checks whether a script pubkey is a P2WSH output.
     #[inline]
     pub fn is_p2wsh(&self) -> bool {
@@ -349,6 +483,28 @@ impl Script {
             && self.0[1] == OP_PUSHBYTES_32.to_u8()
     }
 
+    /// Checks whether a script pubkey is a pay to anchor (P2A) output.
+    #[inline]
+    pub fn is_p2a(&self) -> bool {
+        self.0.len() == 4
+            && self.0[0] == OP_PUSHNUM_1.to_u8()
+            && self.0[1] == OP_PUSHBYTES_2.to_u8()
+            && self.0[2..] == witness_program::P2A_PROGRAM
+    }
+
+    /// Returns the taproot output key if this script is a P2TR output with a **valid** key.
+    ///
+    /// This may return `None` even when [`is_p2tr()`](Self::is_p2tr) returns true, this happens
+    /// when the witness program is not a valid x-only public key, such an output is unspendable.
+    #[inline]
+    pub fn taproot_output_key(&self) -> Option<XOnlyPublicKey> {
+        if self.is_p2tr() {
+            XOnlyPublicKey::from_slice(&self.0[2..]).ok()
+        } else {
+            None
+        }
+    }
+
     /// Check if this is an OP_RETURN output.
     #[inline]
     pub fn is_op_return(&self) -> bool {
@@ -358,6 +514,26 @@ impl Script {
         }
     }
 
+    /// Checks if this is an `OP_RETURN` output that is standard to relay.
+    ///
+    /// A standard `OP_RETURN` output is `OP_RETURN` optionally followed by a single data push of at
+    /// most `max_bytes` bytes. Most nodes use [`MAX_OP_RETURN_DATA_SIZE`] as the limit.
+    ///
+    /// [`MAX_OP_RETURN_DATA_SIZE`]: crate::policy::MAX_OP_RETURN_DATA_SIZE
+    pub fn is_standard_op_return(&self, max_bytes: usize) -> bool {
+        if !self.is_op_return() {
+            return false;
+        }
+
+        let mut instructions = self[1..].instructions();
+        match instructions.next() {
+            None => true,
+            Some(Ok(Instruction::PushBytes(data))) =>
+                data.len() <= max_bytes && instructions.next().is_none(),
+            Some(_) => false,
+        }
+    }
+
     /// Checks whether a script is trivially known to have no satisfying input.
     ///
     /// This method has potentially confusing semantics and an unclear purpose, so it's going to be
@@ -386,6 +562,16 @@ impl Script {
         self.script_hash().map(ScriptBuf::new_p2sh)
     }
 
+    /// Computes the P2SH address corresponding to this redeem script.
+    ///
+    /// This is equivalent to [`Address::p2sh`].
+    pub fn to_p2sh_address(
+        &self,
+        network: impl Into<NetworkKind>,
+    ) -> Result<Address, RedeemScriptSizeError> {
+        Address::p2sh(self, network)
+    }
+
     /// Returns the script code used for spending a P2WPKH output if this script is a script pubkey
     /// for a P2WPKH output. The `scriptCode` is described in [BIP143].
     ///
@@ -412,6 +598,11 @@ impl Script {
     /// Dust depends on the -dustrelayfee value of the Bitcoin Core node you are broadcasting to.
     /// This function uses the default value of 0.00003 BTC/kB (3 sat/vByte).
     ///
+    /// `OP_RETURN` outputs are unspendable so there is no dust threshold for them, zero is returned.
+    ///
+    /// Pay-to-anchor (`OP_1 <0x4e73>`) outputs are not special-cased, at the default fee rate their
+    /// threshold comes out to 240 sat, compared to 294 sat for P2WPKH.
+    ///
     /// To use a custom value, use [`minimal_non_dust_custom`].
     ///
     /// [`minimal_non_dust_custom`]: Script::minimal_non_dust_custom
@@ -427,6 +618,8 @@ impl Script {
     ///
     /// The current default value in Bitcoin Core (as of v26) is 3 sat/vByte.
     ///
+    /// Zero is returned for unspendable `OP_RETURN` outputs.
+    ///
     /// To use the default Bitcoin Core value, use [`minimal_non_dust`].
     ///
     /// [`minimal_non_dust`]: Script::minimal_non_dust
@@ -487,6 +680,25 @@ impl Script {
     /// so do not use this to try and estimate if a taproot script goes over the sigop budget.)
     pub fn count_sigops_legacy(&self) -> usize { self.count_sigops_internal(false) }
 
+    /// Counts the sigops of a segwit spend, as defined by BIP-141.
+    ///
+    /// `self` is the witness program being spent, either the script pubkey or, for nested segwit,
+    /// the P2SH redeem script. A P2WPKH spend counts for one sigop and a P2WSH spend counts the
+    /// sigops of the witness script (the last `witness` element) using accurate counting.
+    ///
+    /// Returns 0 if `self` is not a version 0 witness program, taproot sigops don't count toward
+    /// the block limit. Witness sigops are not scaled, they count directly toward the sigop cost.
+    pub fn count_witness_sigops(&self, witness: &Witness) -> usize {
+        if self.is_p2wpkh() {
+            1
+        } else if self.is_p2wsh() {
+            // Treat the last item of the witness as the witnessScript
+            witness.last().map(Script::from_bytes).map(|s| s.count_sigops()).unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
     fn count_sigops_internal(&self, accurate: bool) -> usize {
         let mut n = 0;
         let mut pushnum_cache = None;
@@ -575,19 +787,49 @@ impl Script {
     }
 
     /// Returns the human-readable assembly representation of the script.
+    ///
+    /// Note that this differs from the `asm` output of Bitcoin Core: every push is prefixed by the
+    /// opcode that performs it (e.g. `OP_PUSHBYTES_20 <hex>`) and small integers are written as
+    /// their opcode names (e.g. `OP_PUSHNUM_2`) rather than as decimal numbers. This makes the
+    /// output unambiguous, i.e. the exact script bytes can be recovered from it. Use
+    /// [`to_core_asm_string`](Self::to_core_asm_string) to match Bitcoin Core.
     pub fn to_asm_string(&self) -> String {
         let mut buf = String::new();
         self.fmt_asm(&mut buf).unwrap();
         buf
     }
 
+    /// Returns the assembly representation of the script as shown by Bitcoin Core.
+    ///
+    /// This matches the `asm` field returned by the `decodescript` RPC: pushes of at most 4 bytes
+    /// are written as decimal numbers, longer pushes as hex without the push opcode, and small
+    /// integer opcodes as their value. Signatures are not decoded, so the sighash type is not
+    /// shown as it is for the script sig `asm` of `decoderawtransaction`. Decoding stops at the
+    /// first malformed push, which is written as `[error]`.
+    pub fn to_core_asm_string(&self) -> String {
+        let mut buf = String::new();
+        fmt_core_asm(self, &mut buf).unwrap();
+        buf
+    }
+
     /// Formats the script as lower-case hex.
     ///
     /// This is a more convenient and performant way to write `format!("{:x}", script)`.
     /// For better performance you should generally prefer displaying the script but if `String` is
     /// required (this is common in tests) this method can be used.
+    ///
+    /// The hex string does not include the compact size length prefix, this is the format
+    /// expected by [`ScriptBuf::from_hex`]. Use [`to_hex_string_prefixed`] to include it.
+    ///
+    /// [`to_hex_string_prefixed`]: Self::to_hex_string_prefixed
     pub fn to_hex_string(&self) -> String { self.as_bytes().to_lower_hex_string() }
 
+    /// Formats the consensus encoding of the script as lower-case hex.
+    ///
+    /// Unlike [`to_hex_string`](Self::to_hex_string) this includes the compact size length
+    /// prefix, as used when the script is serialized inside a transaction.
+    pub fn to_hex_string_prefixed(&self) -> String { crate::consensus::encode::serialize_hex(self) }
+
     /// Returns the first opcode of the script (if there is any).
     pub fn first_opcode(&self) -> Option<Opcode> {
         self.as_bytes().first().copied().map(From::from)
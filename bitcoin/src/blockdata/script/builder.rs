@@ -4,7 +4,7 @@ use core::fmt;
 
 use secp256k1::XOnlyPublicKey;
 
-use crate::key::PublicKey;
+use crate::key::{PubkeyHash, PublicKey, WPubkeyHash};
 use crate::locktime::absolute;
 use crate::opcodes::all::*;
 use crate::opcodes::{self, Opcode};
@@ -77,6 +77,16 @@ impl Builder {
         self.push_slice(x_only_key.serialize())
     }
 
+    /// Adds instructions to push a public key hash onto the stack.
+    pub fn push_pubkey_hash(self, pubkey_hash: PubkeyHash) -> Builder {
+        self.push_slice(pubkey_hash)
+    }
+
+    /// Adds instructions to push a segwit public key hash onto the stack.
+    pub fn push_wpubkey_hash(self, wpubkey_hash: WPubkeyHash) -> Builder {
+        self.push_slice(wpubkey_hash)
+    }
+
     /// Adds a single opcode to the script.
     pub fn push_opcode(mut self, data: Opcode) -> Builder {
         self.0.push_opcode(data);
@@ -65,14 +65,16 @@ use core::fmt;
 use core::ops::{Deref, DerefMut};
 
 use hashes::{hash160, sha256};
+use internals::write_err;
 use io::{BufRead, Write};
 
-use crate::consensus::{encode, Decodable, Encodable};
+use crate::consensus::{encode, Decodable, Encodable, EncodedLen};
 use crate::constants::{MAX_REDEEM_SCRIPT_SIZE, MAX_WITNESS_SCRIPT_SIZE};
 use crate::internal_macros::impl_asref_push_bytes;
 use crate::opcodes::all::*;
 use crate::opcodes::{self, Opcode};
-use crate::prelude::{Borrow, BorrowMut, Box, Cow, DisplayHex, ToOwned, Vec};
+use crate::policy::MAX_OP_RETURN_DATA_SIZE;
+use crate::prelude::{Borrow, BorrowMut, Box, Cow, DisplayHex, String, ToOwned, Vec};
 use crate::OutPoint;
 
 #[rustfmt::skip]                // Keep public re-exports separate.
@@ -628,6 +630,16 @@ impl Encodable for ScriptBuf {
     }
 }
 
+impl EncodedLen for Script {
+    #[inline]
+    fn encoded_len(&self) -> usize { encode::VarInt::from(self.len()).size() + self.len() }
+}
+
+impl EncodedLen for ScriptBuf {
+    #[inline]
+    fn encoded_len(&self) -> usize { self.as_script().encoded_len() }
+}
+
 impl Decodable for ScriptBuf {
     #[inline]
     fn consensus_decode_from_finite_reader<R: BufRead + ?Sized>(
@@ -719,6 +731,34 @@ pub(super) fn bytes_to_asm_fmt(script: &[u8], f: &mut dyn fmt::Write) -> fmt::Re
     Ok(())
 }
 
+/// Writes the assembly decoding of the script in the format used by Bitcoin Core.
+pub(super) fn fmt_core_asm(script: &Script, f: &mut dyn fmt::Write) -> fmt::Result {
+    for (i, instruction) in script.instructions().enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        match instruction {
+            Ok(Instruction::PushBytes(push)) if push.len() <= 4 => {
+                let n = read_scriptint_non_minimal(push.as_bytes()).expect("at most 4 bytes");
+                write!(f, "{}", n)?;
+            }
+            Ok(Instruction::PushBytes(push)) => write!(f, "{:x}", push.as_bytes().as_hex())?,
+            Ok(Instruction::Op(op)) => match op.classify(opcodes::ClassifyContext::Legacy) {
+                opcodes::Class::PushNum(n) => write!(f, "{}", n)?,
+                _ => match op {
+                    OP_CLTV => f.write_str("OP_CHECKLOCKTIMEVERIFY")?,
+                    OP_CSV => f.write_str("OP_CHECKSEQUENCEVERIFY")?,
+                    op if op.to_u8() > OP_CHECKSIGADD.to_u8() && op != OP_INVALIDOPCODE =>
+                        f.write_str("OP_UNKNOWN")?,
+                    op => write!(f, "{:?}", op)?,
+                },
+            },
+            Err(_) => return f.write_str("[error]"),
+        }
+    }
+    Ok(())
+}
+
 /// Ways that a script might fail. Not everything is split up as
 /// much as it could be; patches welcome if more detailed errors
 /// would help you.
@@ -771,6 +811,70 @@ impl std::error::Error for Error {
     }
 }
 
+/// Error parsing a script from its assembly representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AsmParseError {
+    /// Token is neither an opcode name nor hex encoded data.
+    UnknownToken(String),
+    /// Push opcode is not followed by the data to push.
+    MissingPushData(Opcode),
+    /// Data following a push opcode is not valid hex.
+    InvalidPushData(hex::HexToBytesError),
+    /// Data following an `OP_PUSHBYTES_n` opcode is not `n` bytes long.
+    PushLengthMismatch {
+        /// The push opcode.
+        opcode: Opcode,
+        /// The number of bytes pushed by the opcode.
+        expected: usize,
+        /// The number of bytes of data given.
+        actual: usize,
+    },
+    /// Data is too long to be pushed by the opcode.
+    PushTooLong {
+        /// The push opcode.
+        opcode: Opcode,
+        /// The number of bytes of data given.
+        len: usize,
+    },
+}
+
+internals::impl_from_infallible!(AsmParseError);
+
+impl fmt::Display for AsmParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use AsmParseError::*;
+
+        match *self {
+            UnknownToken(ref token) => write!(f, "unknown token in script asm: {}", token),
+            MissingPushData(opcode) => write!(f, "missing data after {}", opcode),
+            InvalidPushData(ref e) => write_err!(f, "invalid push data"; e),
+            PushLengthMismatch { opcode, expected, actual } => write!(
+                f,
+                "{} pushes {} bytes but {} bytes of data were given",
+                opcode, expected, actual
+            ),
+            PushTooLong { opcode, len } =>
+                write!(f, "{} cannot push {} bytes of data", opcode, len),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AsmParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use AsmParseError::*;
+
+        match *self {
+            InvalidPushData(ref e) => Some(e),
+            UnknownToken(_)
+            | MissingPushData(_)
+            | PushLengthMismatch { .. }
+            | PushTooLong { .. } => None,
+        }
+    }
+}
+
 // Our internal error proves that we only return these two cases from `read_uint_iter`.
 // Since it's private we don't bother with trait impls besides From.
 enum UintError {
@@ -824,3 +928,21 @@ impl fmt::Display for WitnessScriptSizeError {
 
 #[cfg(feature = "std")]
 impl std::error::Error for WitnessScriptSizeError {}
+
+/// Error while creating a standard `OP_RETURN` script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpReturnDataSizeError {
+    /// Invalid data size (cannot exceed 80 bytes).
+    pub size: usize,
+}
+
+internals::impl_from_infallible!(OpReturnDataSizeError);
+
+impl fmt::Display for OpReturnDataSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OP_RETURN data size exceeds {} bytes: {}", MAX_OP_RETURN_DATA_SIZE, self.size)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OpReturnDataSizeError {}
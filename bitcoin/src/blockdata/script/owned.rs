@@ -11,11 +11,13 @@ use crate::key::{
 };
 use crate::opcodes::all::*;
 use crate::opcodes::{self, Opcode};
+use crate::policy::MAX_OP_RETURN_DATA_SIZE;
 use crate::prelude::{Box, Vec};
-use crate::script::witness_program::WitnessProgram;
+use crate::script::witness_program::{self, WitnessProgram};
 use crate::script::witness_version::WitnessVersion;
 use crate::script::{
-    opcode_to_verify, Builder, Instruction, PushBytes, Script, ScriptHash, WScriptHash,
+    opcode_to_verify, AsmParseError, Builder, Instruction, OpReturnDataSizeError, PushBytes,
+    Script, ScriptHash, WScriptHash,
 };
 use crate::taproot::TapNodeHash;
 
@@ -85,7 +87,7 @@ impl ScriptBuf {
         Builder::new()
             .push_opcode(OP_DUP)
             .push_opcode(OP_HASH160)
-            .push_slice(pubkey_hash)
+            .push_pubkey_hash(pubkey_hash)
             .push_opcode(OP_EQUALVERIFY)
             .push_opcode(OP_CHECKSIG)
             .into_script()
@@ -124,6 +126,14 @@ impl ScriptBuf {
         ScriptBuf::new_witness_program_unchecked(WitnessVersion::V1, output_key.serialize())
     }
 
+    /// Generates a pay to anchor (P2A) scriptPubkey, `OP_1 <0x4e73>`.
+    ///
+    /// Anchor outputs are spendable by anyone with an empty witness, they are used to bump the
+    /// fee of the transaction creating them using CPFP.
+    pub fn new_p2a() -> Self {
+        ScriptBuf::new_witness_program_unchecked(WitnessVersion::V1, witness_program::P2A_PROGRAM)
+    }
+
     /// Generates P2TR for key spending path for a known [`TweakedPublicKey`].
     pub fn new_p2tr_tweaked(output_key: TweakedPublicKey) -> Self {
         // output key is 32 bytes long, so it's safe to use `new_witness_program_unchecked` (Segwitv1)
@@ -169,16 +179,104 @@ impl ScriptBuf {
     }
 
     /// Generates OP_RETURN-type of scriptPubkey for the given data.
+    ///
+    /// The data size is not checked against the relay policy limit, use
+    /// [`new_standard_op_return`](Self::new_standard_op_return) for that.
     pub fn new_op_return<T: AsRef<PushBytes>>(data: T) -> Self {
         Builder::new().push_opcode(OP_RETURN).push_slice(data).into_script()
     }
 
+    /// Generates a standard OP_RETURN-type of scriptPubkey for the given data.
+    ///
+    /// # Errors
+    ///
+    /// If `data` is larger than [`MAX_OP_RETURN_DATA_SIZE`] bytes, in which case the output would
+    /// not be relayed by most nodes.
+    ///
+    /// [`MAX_OP_RETURN_DATA_SIZE`]: crate::policy::MAX_OP_RETURN_DATA_SIZE
+    pub fn new_standard_op_return<T: AsRef<[u8]>>(data: T) -> Result<Self, OpReturnDataSizeError> {
+        let data = data.as_ref();
+        if data.len() > MAX_OP_RETURN_DATA_SIZE as usize {
+            return Err(OpReturnDataSizeError { size: data.len() });
+        }
+        let data = <&PushBytes>::try_from(data).expect("data size checked above");
+        Ok(Self::new_op_return(data))
+    }
+
     /// Creates a [`ScriptBuf`] from a hex string.
+    ///
+    /// The hex string is the raw script, it must not include the compact size length prefix used
+    /// when the script is consensus encoded (e.g. inside a transaction). Use
+    /// [`consensus::deserialize`](crate::consensus::deserialize) to parse a length-prefixed script.
+    /// Scripts compare equal based on their raw bytes only, regardless of how they were created.
     pub fn from_hex(s: &str) -> Result<Self, hex::HexToBytesError> {
         let v = Vec::from_hex(s)?;
         Ok(ScriptBuf::from_bytes(v))
     }
 
+    /// Parses a script from its human-readable assembly representation.
+    ///
+    /// Accepts the format produced by [`Script::to_asm_string`], opcodes are given by name and
+    /// each push opcode is followed by the hex encoded data it pushes, in which case the script
+    /// uses exactly that opcode even if the push is not minimal. Additionally, hex encoded data
+    /// without a preceding push opcode is pushed using the minimal push opcode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitcoin::ScriptBuf;
+    /// let asm = "OP_DUP OP_HASH160 OP_PUSHBYTES_20 162c5ea71c0b23f5b9022ef047c4a86470a5b070 OP_EQUALVERIFY OP_CHECKSIG";
+    /// let script = ScriptBuf::from_asm(asm).unwrap();
+    /// assert!(script.is_p2pkh());
+    /// assert_eq!(script.to_asm_string(), asm);
+    /// ```
+    pub fn from_asm(s: &str) -> Result<Self, AsmParseError> {
+        use AsmParseError::*;
+
+        let mut script = ScriptBuf::new();
+        let mut tokens = s.split_whitespace();
+        while let Some(token) = tokens.next() {
+            let opcode = match Opcode::from_name(token) {
+                Some(opcode) => opcode,
+                None => {
+                    let data = Vec::from_hex(token).map_err(|_| UnknownToken(token.into()))?;
+                    let data = <&PushBytes>::try_from(data.as_slice())
+                        .map_err(|_| PushTooLong { opcode: OP_PUSHDATA4, len: data.len() })?;
+                    script.push_slice(data);
+                    continue;
+                }
+            };
+            script.push_opcode(opcode);
+
+            let (expected_len, len_size) = match opcode {
+                OP_PUSHDATA1 => (None, 1),
+                OP_PUSHDATA2 => (None, 2),
+                OP_PUSHDATA4 => (None, 4),
+                _ => match opcode.classify(opcodes::ClassifyContext::Legacy) {
+                    opcodes::Class::PushBytes(len) if len > 0 => (Some(len as usize), 0),
+                    _ => continue,
+                },
+            };
+
+            let data = tokens.next().ok_or(MissingPushData(opcode))?;
+            let data = Vec::from_hex(data).map_err(InvalidPushData)?;
+            match expected_len {
+                Some(expected) if data.len() != expected =>
+                    return Err(PushLengthMismatch { opcode, expected, actual: data.len() }),
+                Some(_) => {}
+                None => {
+                    let len = data.len() as u64;
+                    if len >> (8 * len_size) != 0 {
+                        return Err(PushTooLong { opcode, len: data.len() });
+                    }
+                    script.0.extend_from_slice(&len.to_le_bytes()[..len_size]);
+                }
+            }
+            script.0.extend_from_slice(&data);
+        }
+        Ok(script)
+    }
+
     /// Converts byte vector into script.
     ///
     /// This method doesn't (re)allocate.
@@ -6,7 +6,10 @@ use hex_lit::hex;
 
 use super::*;
 use crate::consensus::encode::{deserialize, serialize};
-use crate::crypto::key::{PublicKey, XOnlyPublicKey};
+use crate::crypto::key::{PubkeyHash, PublicKey, WPubkeyHash, XOnlyPublicKey};
+use crate::script::witness_program::WitnessProgram;
+use crate::script::witness_version::WitnessVersion;
+use crate::witness::Witness;
 use crate::FeeRate;
 
 #[test]
@@ -183,6 +186,117 @@ fn script_x_only_key() {
     assert_eq!(script.into_bytes(), &hex!(KEYSTR) as &[u8]);
 }
 
+#[test]
+fn script_taproot_output_key() {
+    // From BIP-86, first receiving address of account 0.
+    let script =
+        ScriptBuf::from_hex("5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c")
+            .unwrap();
+    let key = XOnlyPublicKey::from_str(
+        "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c",
+    )
+    .unwrap();
+    assert_eq!(script.taproot_output_key(), Some(key));
+
+    // Not a point on the curve.
+    let script = ScriptBuf::new_witness_program_unchecked(WitnessVersion::V1, [0xff; 32]);
+    assert!(script.is_p2tr());
+    assert_eq!(script.taproot_output_key(), None);
+
+    // P2WSH
+    let script = ScriptBuf::new_witness_program_unchecked(WitnessVersion::V0, [0x01; 32]);
+    assert_eq!(script.taproot_output_key(), None);
+}
+
+#[test]
+fn script_witness_program_bytes() {
+    // P2WPKH
+    let script = ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+    assert_eq!(script.witness_version(), Some(WitnessVersion::V0));
+    assert_eq!(
+        script.witness_program_bytes(),
+        Some(&hex!("751e76e8199196d454941c45d1b3a323f1433bd6")[..])
+    );
+
+    // P2TR
+    let script =
+        ScriptBuf::from_hex("5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c")
+            .unwrap();
+    assert_eq!(script.witness_version(), Some(WitnessVersion::V1));
+    assert_eq!(
+        script.witness_program_bytes(),
+        Some(&hex!("a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c")[..])
+    );
+
+    // P2PKH
+    let script = ScriptBuf::from_hex("76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac").unwrap();
+    assert_eq!(script.witness_version(), None);
+    assert!(!script.is_witness_program());
+    assert_eq!(script.witness_program_bytes(), None);
+    // Push length does not match the script length.
+    let script = ScriptBuf::from_hex("0015751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+    assert_eq!(script.witness_program_bytes(), None);
+}
+
+#[test]
+fn script_extract_hashes() {
+    let p2pkh = ScriptBuf::from_hex("76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac").unwrap();
+    let pubkey_hash = "162c5ea71c0b23f5b9022ef047c4a86470a5b070".parse::<PubkeyHash>().unwrap();
+    assert_eq!(p2pkh.extract_p2pkh_hash(), Some(pubkey_hash));
+    assert_eq!(p2pkh.extract_p2sh_hash(), None);
+
+    let p2sh = ScriptBuf::from_hex("a914162c5ea71c0b23f5b9022ef047c4a86470a5b07087").unwrap();
+    let script_hash = "162c5ea71c0b23f5b9022ef047c4a86470a5b070".parse::<ScriptHash>().unwrap();
+    assert_eq!(p2sh.extract_p2sh_hash(), Some(script_hash));
+    assert_eq!(p2sh.extract_p2pkh_hash(), None);
+
+    let p2wpkh = ScriptBuf::from_hex("0014162c5ea71c0b23f5b9022ef047c4a86470a5b070").unwrap();
+    assert_eq!(p2wpkh.extract_p2pkh_hash(), None);
+    assert_eq!(p2wpkh.extract_p2sh_hash(), None);
+}
+
+#[test]
+fn script_to_p2sh_address() {
+    use crate::{Address, Network};
+
+    let redeem_script = ScriptBuf::from_hex("52210375e00eb72e29da82b89367947f29ef34afb75e8654f6ea368e0acdfd92976b7c2103a1b26313f430c4b15bb1fdce663207659d8cac749a0e53d70eff01874496feff2103c96d495bfdd5ba4145e3e046fee45e84a8a48ad05bd8dbb395c011a32cf9f88053ae").unwrap();
+    let addr = redeem_script.to_p2sh_address(Network::Bitcoin).unwrap();
+    assert_eq!(addr, Address::p2sh(&redeem_script, Network::Bitcoin).unwrap());
+    assert_eq!(addr.script_pubkey(), redeem_script.to_p2sh().unwrap());
+
+    let too_big = ScriptBuf::from_bytes(vec![0x51; 521]);
+    assert!(too_big.to_p2sh_address(Network::Bitcoin).is_err());
+}
+
+#[test]
+fn script_p2sh_redeem_script() {
+    let redeem_script = Builder::new()
+        .push_opcode(OP_PUSHNUM_2)
+        .push_slice([0x02; 33])
+        .push_slice([0x03; 33])
+        .push_slice([0x02; 33])
+        .push_opcode(OP_PUSHNUM_3)
+        .push_opcode(OP_CHECKMULTISIG)
+        .into_script();
+    let redeem_bytes = <&PushBytes>::try_from(redeem_script.as_bytes()).unwrap();
+    // OP_0 <sig1> <sig2> <redeem script>
+    let script_sig = Builder::new()
+        .push_opcode(OP_PUSHBYTES_0)
+        .push_slice([0x30; 71])
+        .push_slice([0x30; 72])
+        .push_slice(redeem_bytes)
+        .into_script();
+
+    let got = script_sig.p2sh_redeem_script().unwrap();
+    assert_eq!(got, redeem_script.as_script());
+    assert_eq!(ScriptBuf::new_p2sh(got.script_hash().unwrap()).p2sh_redeem_script(), None);
+
+    // Not push only.
+    let script_sig = Builder::new().push_slice([0x30; 71]).push_opcode(OP_DUP).into_script();
+    assert_eq!(script_sig.p2sh_redeem_script(), None);
+    assert_eq!(Script::new().p2sh_redeem_script(), None);
+}
+
 #[test]
 fn script_builder() {
     // from txid 3bb5e6434c11fb93f64574af5d116736510717f2c595eb45b52c28e31622dfff which was in my mempool when I wrote the test
@@ -231,6 +345,28 @@ fn script_generators() {
     );
 }
 
+#[test]
+fn script_builder_key_hashes() {
+    let key =
+        PublicKey::from_str("0234e6a79c5359c613762d537e0e19d86c77c1666d8c9ab050f23acd198e97f93e")
+            .unwrap();
+
+    let p2pkh = Builder::new()
+        .push_opcode(OP_DUP)
+        .push_opcode(OP_HASH160)
+        .push_pubkey_hash(key.pubkey_hash())
+        .push_opcode(OP_EQUALVERIFY)
+        .push_opcode(OP_CHECKSIG)
+        .into_script();
+    assert!(p2pkh.is_p2pkh());
+    assert_eq!(p2pkh, ScriptBuf::new_p2pkh(key.pubkey_hash()));
+
+    let wpubkey_hash = key.wpubkey_hash().unwrap();
+    let p2wpkh = Builder::new().push_int(0).push_wpubkey_hash(wpubkey_hash).into_script();
+    assert!(p2wpkh.is_p2wpkh());
+    assert_eq!(p2wpkh, ScriptBuf::new_p2wpkh(wpubkey_hash));
+}
+
 #[test]
 fn script_builder_verify() {
     let simple = Builder::new().push_verify().into_script();
@@ -280,6 +416,39 @@ fn script_serialize() {
     assert_eq!(serialize(&script.unwrap()), &hex_script as &[u8]);
 }
 
+#[test]
+fn script_from_hex_vs_deserialize() {
+    let raw = "76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac";
+    let from_hex = ScriptBuf::from_hex(raw).unwrap();
+    let deserialized: ScriptBuf =
+        deserialize(&hex!("1976a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac")).unwrap();
+    assert_eq!(from_hex, deserialized);
+    assert_eq!(from_hex.len(), 25);
+
+    // Passing the length-prefixed hex to `from_hex` includes the prefix in the script.
+    let with_prefix =
+        ScriptBuf::from_hex("1976a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac").unwrap();
+    assert_ne!(with_prefix, from_hex);
+    assert_eq!(&with_prefix.as_bytes()[1..], from_hex.as_bytes());
+}
+
+#[test]
+fn script_to_hex_string_prefixed() {
+    let raw = "76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac";
+    let script = ScriptBuf::from_hex(raw).unwrap();
+
+    assert_eq!(script.to_hex_string(), raw);
+    assert_eq!(ScriptBuf::from_hex(&script.to_hex_string()).unwrap(), script);
+
+    let prefixed = script.to_hex_string_prefixed();
+    assert_eq!(prefixed, format!("19{}", raw));
+    let deserialized: ScriptBuf = crate::consensus::encode::deserialize_hex(&prefixed).unwrap();
+    assert_eq!(deserialized, script);
+
+    assert_eq!(ScriptBuf::new().to_hex_string(), "");
+    assert_eq!(ScriptBuf::new().to_hex_string_prefixed(), "00");
+}
+
 #[test]
 fn scriptint_round_trip() {
     fn build_scriptint(n: i64) -> Vec<u8> {
@@ -395,6 +564,22 @@ fn provably_unspendable_test() {
         .is_op_return());
 }
 
+#[test]
+fn script_is_push_only() {
+    assert!(Script::new().is_push_only());
+    assert!(ScriptBuf::from_hex("00").unwrap().is_push_only());
+    // OP_PUSHNUM_1 OP_PUSHBYTES_2 0102 OP_PUSHDATA1 0102 OP_RESERVED OP_PUSHNUM_16
+    assert!(ScriptBuf::from_hex("510201024c0201025060").unwrap().is_push_only());
+
+    // P2PKH script pubkey.
+    let script = ScriptBuf::from_hex("76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac").unwrap();
+    assert!(!script.is_push_only());
+    // OP_PUSHBYTES_1 01 OP_DUP
+    assert!(!ScriptBuf::from_hex("010176").unwrap().is_push_only());
+    // Push past the end of the script.
+    assert!(!ScriptBuf::from_hex("0201").unwrap().is_push_only());
+}
+
 #[test]
 fn op_return_test() {
     assert!(ScriptBuf::from_hex("6aa9149eb21980dc9d413d8eac27314938b9da920ee53e87")
@@ -453,6 +638,103 @@ fn multisig() {
         .is_multisig());
 }
 
+#[test]
+fn script_to_p2wsh_program() {
+    let script = ScriptBuf::from_hex("52210375e00eb72e29da82b89367947f29ef34afb75e8654f6ea368e0acdfd92976b7c2103a1b26313f430c4b15bb1fdce663207659d8cac749a0e53d70eff01874496feff2103c96d495bfdd5ba4145e3e046fee45e84a8a48ad05bd8dbb395c011a32cf9f88053ae").unwrap();
+    let program = script.to_p2wsh_program().unwrap();
+    assert_eq!(program, WitnessProgram::p2wsh(&script).unwrap());
+    assert_eq!(program.version(), WitnessVersion::V0);
+    assert_eq!(program.program().as_bytes(), script.wscript_hash().unwrap().as_byte_array());
+    assert_eq!(ScriptBuf::new_witness_program(&program), script.to_p2wsh().unwrap());
+
+    let too_big = ScriptBuf::from_bytes(vec![0x61; 10_001]);
+    assert!(too_big.to_p2wsh_program().is_err());
+}
+
+#[test]
+fn standard_op_return() {
+    use crate::policy::MAX_OP_RETURN_DATA_SIZE;
+
+    let max = MAX_OP_RETURN_DATA_SIZE as usize;
+    let op_return =
+        |len: usize| ScriptBuf::new_op_return(PushBytesBuf::try_from(vec![0x42; len]).unwrap());
+    assert!(op_return(80).is_standard_op_return(max));
+    assert!(!op_return(81).is_standard_op_return(max));
+    assert!(op_return(81).is_standard_op_return(81));
+    assert!(op_return(0).is_standard_op_return(max));
+    assert!(ScriptBuf::from_bytes(vec![0x6a]).is_standard_op_return(max));
+
+    // More than one push.
+    let script = Builder::new()
+        .push_opcode(OP_RETURN)
+        .push_slice([0x42; 4])
+        .push_slice([0x42; 4])
+        .into_script();
+    assert!(!script.is_standard_op_return(max));
+    // Not a push.
+    let script = Builder::new().push_opcode(OP_RETURN).push_opcode(OP_CHECKSIG).into_script();
+    assert!(!script.is_standard_op_return(max));
+    // Truncated push.
+    assert!(!ScriptBuf::from_bytes(vec![0x6a, 0x05, 0x42]).is_standard_op_return(max));
+
+    let p2pkh = ScriptBuf::from_hex("76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac").unwrap();
+    assert!(!p2pkh.is_standard_op_return(max));
+}
+
+#[test]
+fn parse_multisig() {
+    let keys = [
+        "021c4ac2ecebc398e390e07f045aac5cc421f82f0739c1ce724d3d53964dc6537d",
+        "023a2e9155e0b62f76737605504819a2b4e5ce20653f6c397d7a178ae42ba702f4",
+        "02715e91d37d239dea832f1460e91e368115d8ca6cc23a7da966795abad9e3b699",
+    ]
+    .iter()
+    .map(|s| s.parse::<PublicKey>().unwrap())
+    .collect::<Vec<_>>();
+
+    let script = Builder::new()
+        .push_int(2)
+        .push_key(keys[0])
+        .push_key(keys[1])
+        .push_key(keys[2])
+        .push_int(3)
+        .push_opcode(OP_CHECKMULTISIG)
+        .into_script();
+    assert!(script.is_multisig());
+    assert_eq!(script.parse_multisig(), Some((2, keys.clone())));
+
+    // First multisig, 1-of-2 with uncompressed keys.
+    let script = ScriptBuf::from_hex("514104cc71eb30d653c0c3163990c47b976f3fb3f37cccdcbedb169a1dfef58bbfbfaff7d8a473e7e2e6d317b87bafe8bde97e3cf8f065dec022b51d11fcdd0d348ac4410461cbdcc5409fb4b4d42b51d33381354d80e550078cb532a34bfa2fcfdeb7d76519aecc62770f5b0e4ef8551946d8a540911abe3e7854a26f39f58b25c15342af52ae").unwrap();
+    let (required, parsed) = script.parse_multisig().unwrap();
+    assert_eq!(required, 1);
+    assert_eq!(parsed.len(), 2);
+    assert!(!parsed[0].compressed);
+
+    // Required sigs > num pubkeys.
+    let script = Builder::new()
+        .push_int(3)
+        .push_key(keys[0])
+        .push_key(keys[1])
+        .push_int(2)
+        .push_opcode(OP_CHECKMULTISIG)
+        .into_script();
+    assert_eq!(script.parse_multisig(), None);
+
+    // Not a valid public key, still matches the template.
+    let script = Builder::new()
+        .push_int(1)
+        .push_slice([0x05; 33])
+        .push_int(1)
+        .push_opcode(OP_CHECKMULTISIG)
+        .into_script();
+    assert!(script.is_multisig());
+    assert_eq!(script.parse_multisig(), None);
+
+    // P2PKH.
+    let script = ScriptBuf::from_hex("76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac").unwrap();
+    assert_eq!(script.parse_multisig(), None);
+}
+
 #[test]
 #[cfg(feature = "serde")]
 fn script_json_serialize() {
@@ -465,6 +747,94 @@ fn script_json_serialize() {
     assert_eq!(original, des);
 }
 
+#[test]
+fn script_asm_standard() {
+    // P2PKH
+    let script = ScriptBuf::from_hex("76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac").unwrap();
+    assert_eq!(
+        script.to_asm_string(),
+        "OP_DUP OP_HASH160 OP_PUSHBYTES_20 162c5ea71c0b23f5b9022ef047c4a86470a5b070 OP_EQUALVERIFY OP_CHECKSIG"
+    );
+    // 1-of-2 multisig
+    let script = ScriptBuf::from_hex("512102715e91d37d239dea832f1460e91e368115d8ca6cc23a7da966795abad9e3b6992103244e602b46755f24327142a0517288cebd159eccb6ccf41ea6edf1f601e9af9552ae").unwrap();
+    assert_eq!(
+        script.to_asm_string(),
+        "OP_PUSHNUM_1 OP_PUSHBYTES_33 02715e91d37d239dea832f1460e91e368115d8ca6cc23a7da966795abad9e3b699 OP_PUSHBYTES_33 03244e602b46755f24327142a0517288cebd159eccb6ccf41ea6edf1f601e9af95 OP_PUSHNUM_2 OP_CHECKMULTISIG"
+    );
+    // Unknown opcode
+    assert_eq!(ScriptBuf::from_hex("bb").unwrap().to_asm_string(), "OP_RETURN_187");
+}
+
+#[test]
+fn script_from_asm() {
+    let asm = "OP_DUP OP_HASH160 OP_PUSHBYTES_20 162c5ea71c0b23f5b9022ef047c4a86470a5b070 OP_EQUALVERIFY OP_CHECKSIG";
+    let script = ScriptBuf::from_asm(asm).unwrap();
+    assert_eq!(script.as_bytes(), hex!("76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac"));
+    assert_eq!(script.to_asm_string(), asm);
+
+    // Pushes without an opcode are minimal, explicit push opcodes are kept.
+    let script =
+        ScriptBuf::from_asm("OP_0 0102 OP_PUSHDATA1 0102 OP_PUSHDATA2 03 OP_TRUE").unwrap();
+    assert_eq!(script.as_bytes(), hex!("000201024c0201024d01000351"));
+
+    for hex in [
+        "6363636363686868686800",
+        "512102715e91d37d239dea832f1460e91e368115d8ca6cc23a7da966795abad9e3b6992103244e602b46755f24327142a0517288cebd159eccb6ccf41ea6edf1f601e9af9552ae",
+        "0047304402202457e78cc1b7f50d0543863c27de75d07982bde8359b9e3316adec0aec165f2f02200203fd331c4e4a4a02f48cf1c291e2c0d6b2f7078a784b5b3649fca41f8794d401",
+        "6a0b68656c6c6f20776f726c64",
+        "bb",
+    ] {
+        let script = ScriptBuf::from_hex(hex).unwrap();
+        assert_eq!(ScriptBuf::from_asm(&script.to_asm_string()).unwrap(), script);
+    }
+}
+
+#[test]
+fn script_from_asm_errors() {
+    use crate::script::AsmParseError::*;
+
+    assert_eq!(ScriptBuf::from_asm("OP_FOO"), Err(UnknownToken("OP_FOO".into())));
+    assert_eq!(ScriptBuf::from_asm("OP_PUSHBYTES_2"), Err(MissingPushData(OP_PUSHBYTES_2)));
+    assert!(matches!(ScriptBuf::from_asm("OP_PUSHBYTES_1 zz"), Err(InvalidPushData(_))));
+    assert_eq!(
+        ScriptBuf::from_asm("OP_PUSHBYTES_2 01"),
+        Err(PushLengthMismatch { opcode: OP_PUSHBYTES_2, expected: 2, actual: 1 })
+    );
+    let asm = format!("OP_PUSHDATA1 {}", "00".repeat(256));
+    assert_eq!(ScriptBuf::from_asm(&asm), Err(PushTooLong { opcode: OP_PUSHDATA1, len: 256 }));
+}
+
+#[test]
+fn script_core_asm() {
+    // P2PKH
+    let script = ScriptBuf::from_hex("76a914162c5ea71c0b23f5b9022ef047c4a86470a5b07088ac").unwrap();
+    assert_eq!(
+        script.to_core_asm_string(),
+        "OP_DUP OP_HASH160 162c5ea71c0b23f5b9022ef047c4a86470a5b070 OP_EQUALVERIFY OP_CHECKSIG"
+    );
+    // 1-of-2 multisig
+    let script = ScriptBuf::from_hex("512102715e91d37d239dea832f1460e91e368115d8ca6cc23a7da966795abad9e3b6992103244e602b46755f24327142a0517288cebd159eccb6ccf41ea6edf1f601e9af9552ae").unwrap();
+    assert_eq!(
+        script.to_core_asm_string(),
+        "1 02715e91d37d239dea832f1460e91e368115d8ca6cc23a7da966795abad9e3b699 03244e602b46755f24327142a0517288cebd159eccb6ccf41ea6edf1f601e9af95 2 OP_CHECKMULTISIG"
+    );
+    // Small pushes are numbers, including the empty push, non-minimal encodings and negative zero.
+    let script = ScriptBuf::from_hex("004f0181029000040000008060").unwrap();
+    assert_eq!(script.to_core_asm_string(), "0 -1 -1 144 0 16");
+    let script = ScriptBuf::from_hex("0103b175b26a").unwrap();
+    assert_eq!(
+        script.to_core_asm_string(),
+        "3 OP_CHECKLOCKTIMEVERIFY OP_DROP OP_CHECKSEQUENCEVERIFY OP_RETURN"
+    );
+    // Unknown opcodes and a truncated push.
+    assert_eq!(
+        ScriptBuf::from_hex("babbff").unwrap().to_core_asm_string(),
+        "OP_CHECKSIGADD OP_UNKNOWN OP_INVALIDOPCODE"
+    );
+    assert_eq!(ScriptBuf::from_hex("76024c").unwrap().to_core_asm_string(), "OP_DUP [error]");
+    assert_eq!(ScriptBuf::new().to_core_asm_string(), "");
+}
+
 #[test]
 fn script_asm() {
     assert_eq!(
@@ -697,6 +1067,50 @@ fn defult_dust_value_tests() {
         script_p2pkh.minimal_non_dust_custom(FeeRate::from_sat_per_vb_unchecked(6)),
         crate::Amount::from_sat(1092)
     );
+
+    // Pay-to-anchor (`OP_1 <0x4e73>`) has no special case, Core's GetDustThreshold() treats it as
+    // any other witness program which gives a lower threshold than P2WPKH due to the short program.
+    let script_p2a = ScriptBuf::new_p2a();
+    assert!(script_p2a.is_witness_program());
+    assert_eq!(script_p2a.minimal_non_dust(), crate::Amount::from_sat(240));
+    assert_ne!(script_p2a.minimal_non_dust(), script_p2wpkh.minimal_non_dust());
+
+    // Unspendable, there is no dust threshold.
+    let script_op_return = Builder::new().push_opcode(OP_RETURN).push_slice(b"hello").into_script();
+    assert_eq!(script_op_return.minimal_non_dust(), crate::Amount::ZERO);
+    assert_eq!(
+        script_op_return.minimal_non_dust_custom(FeeRate::from_sat_per_vb_unchecked(6)),
+        crate::Amount::ZERO
+    );
+}
+
+#[test]
+fn count_witness_sigops() {
+    let p2wpkh = ScriptBuf::new_p2wpkh(WPubkeyHash::from_byte_array([0x42; 20]));
+    let witness = Witness::from_slice(&[[0x30; 72].as_slice(), [0x02; 33].as_slice()]);
+    assert_eq!(p2wpkh.count_witness_sigops(&witness), 1);
+
+    let multi = Builder::new()
+        .push_opcode(OP_PUSHNUM_2)
+        .push_slice([3; 33])
+        .push_slice([3; 33])
+        .push_slice([3; 33])
+        .push_opcode(OP_PUSHNUM_3)
+        .push_opcode(OP_CHECKMULTISIG)
+        .into_script();
+    let p2wsh = multi.to_p2wsh().unwrap();
+    let witness = Witness::from_slice(&[&[][..], &[0x30; 72], &[0x30; 72], multi.as_bytes()]);
+    assert_eq!(p2wsh.count_witness_sigops(&witness), 3);
+    // No witness script.
+    assert_eq!(p2wsh.count_witness_sigops(&Witness::new()), 0);
+
+    // Not a v0 witness program.
+    let p2pkh = ScriptBuf::new_p2pkh(PubkeyHash::from_byte_array([0x42; 20]));
+    assert_eq!(p2pkh.count_witness_sigops(&witness), 0);
+    let p2tr = ScriptBuf::new_witness_program(
+        &WitnessProgram::new(WitnessVersion::V1, &[0x42; 32]).unwrap(),
+    );
+    assert_eq!(p2tr.count_witness_sigops(&witness), 0);
 }
 
 #[test]
@@ -908,3 +1322,88 @@ fn instruction_script_num_parse() {
         Some(Ok(Instruction::PushBytes(PushBytes::empty()))),
     );
 }
+
+#[test]
+fn starts_ends_with_opcodes() {
+    let p2pkh = ScriptBuf::new_p2pkh(PubkeyHash::from_byte_array([0x42; 20]));
+    assert!(p2pkh.starts_with_opcodes(&[OP_DUP, OP_HASH160]));
+    // The hash push is skipped.
+    assert!(p2pkh.starts_with_opcodes(&[OP_DUP, OP_HASH160, OP_EQUALVERIFY]));
+    assert!(p2pkh.starts_with_opcodes(&[]));
+    assert!(!p2pkh.starts_with_opcodes(&[OP_HASH160]));
+    assert!(!p2pkh.starts_with_opcodes(&[OP_DUP, OP_HASH160, OP_EQUALVERIFY, OP_CHECKSIG, OP_NOP]));
+
+    assert!(p2pkh.ends_with_opcodes(&[OP_EQUALVERIFY, OP_CHECKSIG]));
+    assert!(p2pkh.ends_with_opcodes(&[OP_DUP, OP_HASH160, OP_EQUALVERIFY, OP_CHECKSIG]));
+    assert!(p2pkh.ends_with_opcodes(&[]));
+    assert!(!p2pkh.ends_with_opcodes(&[OP_EQUALVERIFY]));
+
+    // OP_0 is a push.
+    let p2wpkh = ScriptBuf::new_p2wpkh(WPubkeyHash::from_byte_array([0x42; 20]));
+    assert!(!p2wpkh.starts_with_opcodes(&[OP_PUSHBYTES_0]));
+    assert!(p2wpkh.ends_with_opcodes(&[]));
+
+    // Truncated push.
+    let invalid = ScriptBuf::from_bytes(vec![OP_DUP.to_u8(), OP_PUSHBYTES_4.to_u8(), 0x01]);
+    assert!(invalid.starts_with_opcodes(&[OP_DUP]));
+    assert!(!invalid.starts_with_opcodes(&[OP_DUP, OP_CHECKSIG]));
+    assert!(!invalid.ends_with_opcodes(&[]));
+}
+
+#[test]
+fn p2a() {
+    let p2a = ScriptBuf::new_p2a();
+    assert_eq!(p2a.as_bytes(), hex!("51024e73"));
+    assert!(p2a.is_p2a());
+    assert_eq!(p2a.witness_version(), Some(WitnessVersion::V1));
+    assert!(!p2a.is_p2tr());
+
+    let p2wpkh = ScriptBuf::new_p2wpkh(WPubkeyHash::from_byte_array([0x42; 20]));
+    assert!(!p2wpkh.is_p2a());
+    // Same program with another witness version.
+    let v2 = ScriptBuf::new_witness_program_unchecked(WitnessVersion::V2, [0x4e, 0x73]);
+    assert!(!v2.is_p2a());
+}
+
+#[test]
+fn push_lock_time_and_sequence() {
+    use crate::locktime::absolute;
+    use crate::Sequence;
+
+    let lock_time = absolute::LockTime::from_height(500_000).unwrap();
+    let script = Builder::new()
+        .push_lock_time(lock_time)
+        .push_opcode(OP_CLTV)
+        .push_opcode(OP_DROP)
+        .into_script();
+    // 500_000 is 0x07a120, encoded as a little endian script number.
+    assert_eq!(script.as_bytes(), hex!("0320a107b175"));
+
+    // The high bit of 144 is set so a zero byte is needed to keep the number positive.
+    let script = Builder::new()
+        .push_sequence(Sequence::from_height(144))
+        .push_opcode(OP_CSV)
+        .push_opcode(OP_DROP)
+        .into_script();
+    assert_eq!(script.as_bytes(), hex!("029000b275"));
+
+    // Small values use the minimal push opcodes.
+    let script = Builder::new().push_lock_time(absolute::LockTime::ZERO).into_script();
+    assert_eq!(script.as_bytes(), [OP_PUSHBYTES_0.to_u8()]);
+    let script = Builder::new().push_sequence(Sequence::from_height(16)).into_script();
+    assert_eq!(script.as_bytes(), [OP_PUSHNUM_16.to_u8()]);
+}
+
+#[test]
+fn new_standard_op_return() {
+    let script = ScriptBuf::new_standard_op_return([0xab; 80]).unwrap();
+    assert!(script.is_op_return());
+    assert!(script.is_standard_op_return(80));
+    assert_eq!(script, ScriptBuf::new_op_return(<&PushBytes>::try_from(&[0xab; 80][..]).unwrap()));
+
+    let err = ScriptBuf::new_standard_op_return([0xab; 81]).unwrap_err();
+    assert_eq!(err, OpReturnDataSizeError { size: 81 });
+
+    let empty = ScriptBuf::new_standard_op_return([]).unwrap();
+    assert_eq!(empty.as_bytes(), [OP_RETURN.to_u8(), 0x00]);
+}
@@ -8,12 +8,17 @@
 //! [BIP141]: <https://github.com/bitcoin/bips/blob/master/bip-0141.mediawiki>
 
 use core::fmt;
+use core::str::FromStr;
 
+use bech32::primitives::gf32::Fe32;
+use hex::{DisplayHex, FromHex};
 use internals::array_vec::ArrayVec;
+use internals::write_err;
 use secp256k1::{Secp256k1, Verification};
 
 use crate::crypto::key::{CompressedPublicKey, TapTweak, TweakedPublicKey, UntweakedPublicKey};
-use crate::script::witness_version::WitnessVersion;
+use crate::prelude::Vec;
+use crate::script::witness_version::{self, WitnessVersion};
 use crate::script::{PushBytes, Script, WScriptHash, WitnessScriptSizeError};
 use crate::taproot::TapNodeHash;
 
@@ -23,6 +28,9 @@ pub const MIN_SIZE: usize = 2;
 /// The maximum byte size of a segregated witness program.
 pub const MAX_SIZE: usize = 40;
 
+/// The witness program of a pay to anchor (P2A) output, used with witness version 1.
+pub(crate) const P2A_PROGRAM: [u8; 2] = [0x4e, 0x73];
+
 /// The segregated witness program.
 ///
 /// The segregated witness program is technically only the program bytes _excluding_ the witness
@@ -106,6 +114,13 @@ impl WitnessProgram {
     /// Returns the witness program version.
     pub fn version(&self) -> WitnessVersion { self.version }
 
+    /// Returns the witness program version as a bech32 field element.
+    ///
+    /// Useful when bech32 encoding the witness program with a custom human-readable part.
+    pub fn version_fe32(&self) -> Fe32 {
+        Fe32::try_from(self.version.to_num()).expect("version nums 0-16 are valid fe32 values")
+    }
+
     /// Returns the witness program.
     pub fn program(&self) -> &PushBytes {
         self.program
@@ -114,6 +129,13 @@ impl WitnessProgram {
             .expect("witness programs are always smaller than max size of PushBytes")
     }
 
+    /// Returns true if this is the witness program `program` with version `version`.
+    ///
+    /// Useful when scanning scripts since it avoids constructing a `WitnessProgram`.
+    pub fn matches_bytes(&self, version: WitnessVersion, program: &[u8]) -> bool {
+        self.version == version && self.program.as_slice() == program
+    }
+
     /// Returns true if this witness program is for a P2WPKH output.
     pub fn is_p2wpkh(&self) -> bool {
         self.version == WitnessVersion::V0 && self.program.len() == 20
@@ -128,6 +150,76 @@ impl WitnessProgram {
     pub fn is_p2tr(&self) -> bool { self.version == WitnessVersion::V1 && self.program.len() == 32 }
 }
 
+/// Formats the witness program as `v<version>:<hex>` e.g., `v1:abcd...`.
+///
+/// This is a compact debugging and configuration format, it is not an address and carries no
+/// network information.
+impl fmt::Display for WitnessProgram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "v{}:{:x}", self.version, self.program.as_slice().as_hex())
+    }
+}
+
+/// Parses a witness program from the `v<version>:<hex>` form produced by [`fmt::Display`].
+impl FromStr for WitnessProgram {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (version, program) = s.split_once(':').ok_or(ParseError::Format)?;
+        let version = version.strip_prefix('v').ok_or(ParseError::Format)?;
+        let version = version.parse::<WitnessVersion>().map_err(ParseError::Version)?;
+        let program = Vec::from_hex(program).map_err(ParseError::Hex)?;
+        Ok(WitnessProgram::new(version, &program)?)
+    }
+}
+
+/// Error parsing a [`WitnessProgram`] from a `v<version>:<hex>` string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The string is not of the form `v<version>:<hex>`.
+    Format,
+    /// Invalid witness version.
+    Version(witness_version::FromStrError),
+    /// Invalid hex encoding of the program.
+    Hex(hex::HexToBytesError),
+    /// The program is invalid for the given version.
+    Program(Error),
+}
+
+internals::impl_from_infallible!(ParseError);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ParseError::*;
+
+        match *self {
+            Format => f.write_str("witness program must be of the form v<version>:<hex>"),
+            Version(ref e) => write_err!(f, "invalid witness version"; e),
+            Hex(ref e) => write_err!(f, "invalid witness program hex"; e),
+            Program(ref e) => write_err!(f, "invalid witness program"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use ParseError::*;
+
+        match *self {
+            Format => None,
+            Version(ref e) => Some(e),
+            Hex(ref e) => Some(e),
+            Program(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<Error> for ParseError {
+    fn from(e: Error) -> Self { Self::Program(e) }
+}
+
 /// Witness program error.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -163,3 +255,61 @@ impl std::error::Error for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hex_lit::hex;
+
+    use super::*;
+
+    #[test]
+    fn matches_bytes() {
+        let bytes = hex!("751e76e8199196d454941c45d1b3a323f1433bd6");
+        let program = WitnessProgram::new(WitnessVersion::V0, &bytes).unwrap();
+        assert!(program.is_p2wpkh());
+
+        assert!(program.matches_bytes(WitnessVersion::V0, &bytes));
+        assert!(!program.matches_bytes(WitnessVersion::V1, &bytes));
+        assert!(!program.matches_bytes(WitnessVersion::V0, &bytes[..19]));
+        assert!(!program.matches_bytes(WitnessVersion::V0, &[0x75; 20]));
+    }
+
+    #[test]
+    fn version_fe32() {
+        let program = WitnessProgram::new(WitnessVersion::V0, &[0x00; 20]).unwrap();
+        assert_eq!(program.version_fe32(), Fe32::Q);
+
+        let program = WitnessProgram::new(WitnessVersion::V1, &[0x00; 32]).unwrap();
+        assert_eq!(program.version_fe32(), Fe32::P);
+
+        let program = WitnessProgram::new(WitnessVersion::V16, &[0x00; 2]).unwrap();
+        assert_eq!(program.version_fe32(), Fe32::S);
+        assert_eq!(program.version_fe32().to_u8(), 16);
+    }
+
+    #[test]
+    fn display_from_str_round_trip() {
+        let bytes = hex!("751e76e8199196d454941c45d1b3a323f1433bd6");
+        let program = WitnessProgram::new(WitnessVersion::V0, &bytes).unwrap();
+        let s = program.to_string();
+        assert_eq!(s, "v0:751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert_eq!(s.parse::<WitnessProgram>().unwrap(), program);
+
+        let program = WitnessProgram::new(WitnessVersion::V1, &[0xab; 32]).unwrap();
+        let s = program.to_string();
+        assert_eq!(s, format!("v1:{}", "ab".repeat(32)));
+        assert_eq!(s.parse::<WitnessProgram>().unwrap(), program);
+    }
+
+    #[test]
+    fn from_str_invalid() {
+        assert_eq!("0:abcd".parse::<WitnessProgram>(), Err(ParseError::Format));
+        assert_eq!("v1abcd".parse::<WitnessProgram>(), Err(ParseError::Format));
+        assert!(matches!("v17:abcd".parse::<WitnessProgram>(), Err(ParseError::Version(_))));
+        assert!(matches!("v1:abcz".parse::<WitnessProgram>(), Err(ParseError::Hex(_))));
+        assert_eq!(
+            "v0:abcd".parse::<WitnessProgram>(),
+            Err(ParseError::Program(Error::InvalidSegwitV0Length(2)))
+        );
+    }
+}
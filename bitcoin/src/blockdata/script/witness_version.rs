@@ -240,6 +240,8 @@ pub struct TryFromError {
 
 impl TryFromError {
     /// Returns the invalid non-witness version integer.
+    ///
+    /// When converting from an [`Opcode`] this is the byte value of the opcode.
     pub fn invalid_version(&self) -> u8 { self.invalid }
 }
 
@@ -251,3 +253,31 @@ impl fmt::Display for TryFromError {
 
 #[cfg(feature = "std")]
 impl std::error::Error for TryFromError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_from_u8() {
+        assert_eq!(WitnessVersion::try_from(0), Ok(WitnessVersion::V0));
+        assert_eq!(WitnessVersion::try_from(16), Ok(WitnessVersion::V16));
+
+        let err = WitnessVersion::try_from(17).unwrap_err();
+        assert_eq!(err.invalid_version(), 17);
+        assert_eq!(err.to_string(), "invalid witness script version: 17");
+
+        for n in 0..=16 {
+            assert_eq!(WitnessVersion::try_from(n).unwrap().to_num(), n);
+        }
+    }
+
+    #[test]
+    fn try_from_opcode() {
+        assert_eq!(WitnessVersion::try_from(OP_PUSHNUM_1), Ok(WitnessVersion::V1));
+        assert_eq!(WitnessVersion::try_from(OP_PUSHNUM_16), Ok(WitnessVersion::V16));
+
+        let err = WitnessVersion::try_from(OP_NOP).unwrap_err();
+        assert_eq!(err.invalid_version(), OP_NOP.to_u8());
+    }
+}
@@ -18,7 +18,10 @@ use io::{BufRead, Write};
 use units::parse::{self, PrefixedHexError, UnprefixedHexError};
 
 use super::Weight;
-use crate::consensus::{encode, Decodable, Encodable};
+use crate::address::Address;
+use crate::amount::CheckedSum;
+use crate::block::{self, Bip34Error};
+use crate::consensus::{encode, Decodable, Encodable, EncodedLen};
 use crate::internal_macros::{impl_consensus_encoding, impl_hashencode};
 use crate::locktime::absolute::{self, Height, Time};
 use crate::locktime::relative::{self, TimeOverflowError};
@@ -54,7 +57,10 @@ impl Txid {
     ///
     /// This is used as the "txid" of the dummy input of a coinbase transaction. It is
     /// not a real TXID and should not be used in other contexts.
-    pub fn all_zeros() -> Self { Self::from_byte_array([0; 32]) }
+    pub const fn all_zeros() -> Self { Self::from_byte_array([0; 32]) }
+
+    /// Returns true if this is the "all zeros" TXID.
+    pub fn is_all_zeros(&self) -> bool { *self == Self::all_zeros() }
 }
 
 impl Wtxid {
@@ -64,7 +70,10 @@ impl Wtxid {
     /// since the coinbase transaction contains a commitment to all transactions' wTXIDs
     /// but naturally cannot commit to its own. It is not a real wTXID and should not be
     /// used in other contexts.
-    pub fn all_zeros() -> Self { Self::from_byte_array([0; 32]) }
+    pub const fn all_zeros() -> Self { Self::from_byte_array([0; 32]) }
+
+    /// Returns true if this is the "all zeros" wTXID.
+    pub fn is_all_zeros(&self) -> bool { *self == Self::all_zeros() }
 }
 
 /// The marker MUST be a 1-byte zero value: 0x00. (BIP-141)
@@ -91,15 +100,21 @@ impl OutPoint {
     /// The number of bytes that an outpoint contributes to the size of a transaction.
     const SIZE: usize = 32 + 4; // The serialized lengths of txid and vout.
 
+    /// The "null" `OutPoint`, spent by the single input of a coinbase transaction.
+    ///
+    /// This value is used for coinbase transactions because they don't have any previous outputs.
+    pub const COINBASE: OutPoint =
+        OutPoint { txid: Txid::from_byte_array([0; 32]), vout: u32::MAX };
+
     /// Creates a new [`OutPoint`].
     #[inline]
     pub const fn new(txid: Txid, vout: u32) -> OutPoint { OutPoint { txid, vout } }
 
     /// Creates a "null" `OutPoint`.
     ///
-    /// This value is used for coinbase transactions because they don't have any previous outputs.
+    /// This is the same as [`OutPoint::COINBASE`].
     #[inline]
-    pub fn null() -> OutPoint { OutPoint { txid: Txid::all_zeros(), vout: u32::MAX } }
+    pub fn null() -> OutPoint { OutPoint::COINBASE }
 
     /// Checks if an `OutPoint` is "null".
     ///
@@ -117,11 +132,11 @@ impl OutPoint {
     /// assert!(tx.input[0].previous_output.is_null());
     /// ```
     #[inline]
-    pub fn is_null(&self) -> bool { *self == OutPoint::null() }
+    pub fn is_null(&self) -> bool { *self == OutPoint::COINBASE }
 }
 
 impl Default for OutPoint {
-    fn default() -> Self { OutPoint::null() }
+    fn default() -> Self { OutPoint::COINBASE }
 }
 
 impl fmt::Display for OutPoint {
@@ -246,7 +261,7 @@ impl TxIn {
     /// Returns the input base weight.
     ///
     /// Base weight excludes the witness and script.
-    const BASE_WEIGHT: Weight =
+    pub(crate) const BASE_WEIGHT: Weight =
         Weight::from_vb_unwrap(OutPoint::SIZE as u64 + Sequence::SIZE as u64);
 
     /// Returns true if this input enables the [`absolute::LockTime`] (aka `nLockTime`) of its
@@ -606,6 +621,15 @@ impl TxOut {
     pub fn minimal_non_dust_custom(script_pubkey: ScriptBuf, dust_relay_fee: FeeRate) -> Self {
         TxOut { value: script_pubkey.minimal_non_dust_custom(dust_relay_fee), script_pubkey }
     }
+
+    /// Returns true if this output is dust at the given dust relay fee rate, per current Core
+    /// policy.
+    ///
+    /// An output is dust if its value is less than [`Script::minimal_non_dust_custom`] for its
+    /// script pubkey. Use [`FeeRate::DUST`] for the default Bitcoin Core value.
+    pub fn is_dust(&self, dust_relay_fee: FeeRate) -> bool {
+        self.value < self.script_pubkey.minimal_non_dust_custom(dust_relay_fee)
+    }
 }
 
 /// Returns the total number of bytes that this script pubkey would contribute to a transaction.
@@ -783,6 +807,69 @@ impl Transaction {
         Wtxid(sha256d::Hash::from_engine(enc))
     }
 
+    /// Computes the leaf of this transaction in the block merkle tree, this is the txid.
+    pub fn merkle_leaf(&self) -> Txid { self.compute_txid() }
+
+    /// Computes the leaf of this transaction in the block witness merkle tree.
+    ///
+    /// This is the wtxid, except for the coinbase transaction whose leaf is all zeros as specified
+    /// by BIP-141.
+    pub fn witness_merkle_leaf(&self) -> Wtxid {
+        if self.is_coinbase() {
+            Wtxid::all_zeros()
+        } else {
+            self.compute_wtxid()
+        }
+    }
+
+    /// Returns a copy of this transaction with the witness of every input cleared.
+    ///
+    /// The txid commits to exactly the data that remains, so the wtxid of the returned transaction
+    /// is equal to the [`Transaction::compute_txid`] of this one. To compute the txid there is no
+    /// need to call this function since `compute_txid` already ignores witness data.
+    ///
+    /// Note that a transaction with no inputs is still serialized using the segwit format to
+    /// avoid ambiguity (see the docs on [`Transaction`]).
+    pub fn without_witnesses(&self) -> Transaction {
+        Transaction {
+            version: self.version,
+            lock_time: self.lock_time,
+            input: self
+                .input
+                .iter()
+                .map(|txin| TxIn { witness: Witness::default(), ..txin.clone() })
+                .collect(),
+            output: self.output.clone(),
+        }
+    }
+
+    /// Returns true if any input of this transaction has a non-empty witness.
+    #[inline]
+    pub fn has_witness(&self) -> bool { self.input.iter().any(|input| !input.witness.is_empty()) }
+
+    /// Consensus encodes the inputs of this transaction, prefixed by their count.
+    ///
+    /// This is the same encoding as used for the inputs inside the serialized transaction, it does
+    /// not include the witnesses.
+    pub fn encode_inputs<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
+        self.input.consensus_encode(w)
+    }
+
+    /// Consensus encodes the outputs of this transaction, prefixed by their count.
+    ///
+    /// This is the same encoding as used for the outputs inside the serialized transaction.
+    pub fn encode_outputs<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
+        self.output.consensus_encode(w)
+    }
+
+    /// Returns true if every input of this transaction has a non-empty scriptSig or witness.
+    ///
+    /// This is a cheap check that the transaction has been signed, it does not validate the
+    /// signatures. A transaction with no inputs is considered finalized.
+    pub fn is_finalized(&self) -> bool {
+        self.input.iter().all(|input| !input.script_sig.is_empty() || !input.witness.is_empty())
+    }
+
     /// Returns the weight of this transaction, as defined by BIP-141.
     ///
     /// > Transaction weight is defined as Base transaction size * 3 + Total transaction size (ie.
@@ -827,6 +914,8 @@ impl Transaction {
     ///
     /// > Total transaction size is the transaction size in bytes serialized as described in BIP144,
     /// > including base data and witness data.
+    ///
+    /// This is the length of the consensus encoding of this transaction.
     #[inline]
     pub fn total_size(&self) -> usize {
         let mut size: usize = 4; // Serialized length of a u32 for the version number.
@@ -849,6 +938,28 @@ impl Transaction {
         size + absolute::LockTime::SIZE
     }
 
+    /// Returns the weight of this transaction split by component.
+    ///
+    /// The components add up to [`Transaction::weight`].
+    pub fn weight_breakdown(&self) -> WeightBreakdown {
+        let overhead = 4 // Version.
+            + VarInt::from(self.input.len()).size()
+            + VarInt::from(self.output.len()).size()
+            + absolute::LockTime::SIZE;
+        let witness = if self.uses_segwit_serialization() {
+            2 + self.input.iter().map(|input| input.witness.size()).sum::<usize>()
+        } else {
+            0
+        };
+
+        WeightBreakdown {
+            overhead: Weight::from_non_witness_data_size(overhead as u64),
+            inputs: self.input.iter().map(TxIn::legacy_weight).sum(),
+            outputs: self.output.iter().map(TxOut::weight).sum(),
+            witness: Weight::from_witness_data_size(witness as u64),
+        }
+    }
+
     /// Returns the "virtual size" (vsize) of this transaction.
     ///
     /// Will be `ceil(weight / 4.0)`. Note this implements the virtual size as per [`BIP141`], which
@@ -866,6 +977,22 @@ impl Transaction {
         self.weight().to_vbytes_ceil() as usize
     }
 
+    /// Returns the fee this transaction needs to pay to meet `fee_rate`.
+    ///
+    /// The fee is computed from the [`vsize`](Self::vsize), as done by Bitcoin Core when checking
+    /// the minimum relay fee, and is rounded up. Returns `None` on overflow.
+    pub fn minimum_fee(&self, fee_rate: FeeRate) -> Option<Amount> {
+        fee_rate.fee_vb(self.vsize() as u64)
+    }
+
+    /// Returns the sum of the values of all outputs, or `None` if the sum overflows.
+    ///
+    /// The total is not checked against [`Amount::MAX_MONEY`], only a consensus invalid transaction
+    /// can exceed it.
+    pub fn total_output_value(&self) -> Option<Amount> {
+        self.output.iter().map(|txout| txout.value).checked_sum()
+    }
+
     /// Checks if this is a coinbase transaction.
     ///
     /// The first transaction in the block distributes the mining reward and is called the coinbase
@@ -877,6 +1004,20 @@ impl Transaction {
         self.input.len() == 1 && self.input[0].previous_output.is_null()
     }
 
+    /// Returns the block height encoded in the script sig of this coinbase transaction according to
+    /// BIP34.
+    ///
+    /// Returns [`Bip34Error::NotPresent`] if this is not a coinbase transaction. Note that the
+    /// height is only required to be present in blocks with version 2 or above, see
+    /// [`Block::bip34_block_height`](crate::Block::bip34_block_height). The block version is not
+    /// known here, so for coinbases that predate BIP34 the returned number can be arbitrary.
+    pub fn bip34_block_height(&self) -> Result<u64, Bip34Error> {
+        if !self.is_coinbase() {
+            return Err(Bip34Error::NotPresent);
+        }
+        block::bip34_height_from_script_sig(&self.input[0].script_sig)
+    }
+
     /// Returns `true` if the transaction itself opted in to be BIP-125-replaceable (RBF).
     ///
     /// # Warning
@@ -988,21 +1129,6 @@ impl Transaction {
     where
         S: FnMut(&OutPoint) -> Option<TxOut>,
     {
-        fn count_sigops_with_witness_program(witness: &Witness, witness_program: &Script) -> usize {
-            if witness_program.is_p2wpkh() {
-                1
-            } else if witness_program.is_p2wsh() {
-                // Treat the last item of the witness as the witnessScript
-                return witness
-                    .last()
-                    .map(Script::from_bytes)
-                    .map(|s| s.count_sigops())
-                    .unwrap_or(0);
-            } else {
-                0
-            }
-        }
-
         fn count_sigops(prevout: TxOut, input: &TxIn) -> usize {
             let script_sig = &input.script_sig;
             let witness = &input.witness;
@@ -1022,7 +1148,7 @@ impl Transaction {
             };
 
             // This will return 0 if the redeemScript wasn't a witness program
-            count_sigops_with_witness_program(witness, witness_program)
+            witness_program.count_witness_sigops(witness)
         }
 
         let mut count: usize = 0;
@@ -1036,7 +1162,7 @@ impl Transaction {
 
     /// Returns whether or not to serialize transaction as specified in BIP-144.
     fn uses_segwit_serialization(&self) -> bool {
-        if self.input.iter().any(|input| !input.witness.is_empty()) {
+        if self.has_witness() {
             return true;
         }
         // To avoid serialization ambiguity, no inputs means we use BIP141 serialization (see
@@ -1059,6 +1185,58 @@ impl Transaction {
             .get(output_index)
             .ok_or(IndexOutOfBoundsError { index: output_index, length: self.output.len() }.into())
     }
+
+    /// Returns the outpoint referencing the output at `vout` if it exists.
+    ///
+    /// Note that this computes the txid, if creating outpoints for several outputs prefer calling
+    /// [`Transaction::compute_txid`] once.
+    pub fn outpoint(&self, vout: u32) -> Result<OutPoint, OutputsIndexError> {
+        self.tx_out(vout as usize)?;
+        Ok(OutPoint { txid: self.compute_txid(), vout })
+    }
+
+    /// Returns a copy of this transaction with the witness of the input at `input_index` replaced.
+    ///
+    /// Note that this clones the whole transaction, if you own it prefer setting the witness of
+    /// the input directly.
+    pub fn with_input_witness(
+        &self,
+        input_index: usize,
+        witness: Witness,
+    ) -> Result<Transaction, InputsIndexError> {
+        self.tx_in(input_index)?;
+        let mut tx = self.clone();
+        tx.input[input_index].witness = witness;
+        Ok(tx)
+    }
+
+    /// Returns the index of the first output paying to `address`, and the output itself.
+    ///
+    /// Compares the script pubkeys without allocating, see [`Address::matches_script_pubkey`].
+    pub fn find_output_to(&self, address: &Address) -> Option<(usize, &TxOut)> {
+        self.output
+            .iter()
+            .enumerate()
+            .find(|(_, txout)| address.matches_script_pubkey(&txout.script_pubkey))
+    }
+}
+
+/// The weight of a transaction split by component, see [`Transaction::weight_breakdown`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WeightBreakdown {
+    /// The version, lock time and the input and output counts.
+    pub overhead: Weight,
+    /// The inputs excluding their witnesses.
+    pub inputs: Weight,
+    /// The outputs.
+    pub outputs: Weight,
+    /// The witnesses of all inputs and the segwit marker and flag, zero for legacy transactions.
+    pub witness: Weight,
+}
+
+impl WeightBreakdown {
+    /// Returns the total weight, this is the same as [`Transaction::weight`].
+    pub fn total(&self) -> Weight { self.overhead + self.inputs + self.outputs + self.witness }
 }
 
 /// Error attempting to do an out of bounds access on the transaction inputs vector.
@@ -1166,6 +1344,22 @@ impl fmt::Display for Version {
 
 impl_consensus_encoding!(TxOut, value, script_pubkey);
 
+impl EncodedLen for TxOut {
+    #[inline]
+    fn encoded_len(&self) -> usize { self.size() }
+}
+
+impl EncodedLen for TxIn {
+    /// The encoding of a single input does not include its witness so this is the base size.
+    #[inline]
+    fn encoded_len(&self) -> usize { self.base_size() }
+}
+
+impl EncodedLen for Transaction {
+    #[inline]
+    fn encoded_len(&self) -> usize { self.total_size() }
+}
+
 impl Encodable for OutPoint {
     fn consensus_encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
         let len = self.txid.consensus_encode(w)?;
@@ -1637,10 +1831,14 @@ mod tests {
     use super::*;
     use crate::consensus::encode::{deserialize, serialize};
     use crate::constants::WITNESS_SCALE_FACTOR;
+    use crate::key::WPubkeyHash;
     use crate::sighash::EcdsaSighashType;
 
     const SOME_TX: &str = "0100000001a15d57094aa7a21a28cb20b59aab8fc7d1149a3bdbcddba9c622e4f5f6a99ece010000006c493046022100f93bb0e7d8db7bd46e40132d1f8242026e045f03a0efe71bbb8e3f475e970d790221009337cd7f1f929f00cc6ff01f03729b069a7c21b59b1736ddfee5db5946c5da8c0121033b9b137ee87d5a812d6f506efdd37f0affa7ffc310711c06c7f3e097c9447c52ffffffff0100e1f505000000001976a9140389035a9225b3839e2bbf32d826a1e222031fd888ac00000000";
 
+    /// A P2SH-P2WPKH spend with one input and one output.
+    const SEGWIT_TX: &str = "02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c0100000000ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd2710e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed8941055d3bcb8627d085e94553e62f057dcc00000000";
+
     #[test]
     fn encode_to_unsized_writer() {
         let mut buf = [0u8; 1024];
@@ -1652,6 +1850,85 @@ mod tests {
         assert_eq!(raw_tx, &buf[..size]);
     }
 
+    #[test]
+    fn input_output_index_bounds() {
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+
+        assert_eq!(tx.tx_in(0), Ok(&tx.input[0]));
+        assert_eq!(
+            tx.tx_in(1),
+            Err(InputsIndexError(IndexOutOfBoundsError { index: 1, length: 1 }))
+        );
+        assert_eq!(tx.tx_out(0), Ok(&tx.output[0]));
+        assert_eq!(
+            tx.tx_out(1),
+            Err(OutputsIndexError(IndexOutOfBoundsError { index: 1, length: 1 }))
+        );
+
+        assert_eq!(tx.outpoint(0), Ok(OutPoint { txid: tx.compute_txid(), vout: 0 }));
+        assert_eq!(
+            tx.outpoint(u32::MAX),
+            Err(OutputsIndexError(IndexOutOfBoundsError { index: u32::MAX as usize, length: 1 }))
+        );
+    }
+
+    #[test]
+    fn with_input_witness() {
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        let witness = Witness::from_slice(&[vec![0x01; 72], vec![0x02; 33]]);
+
+        let signed = tx.with_input_witness(0, witness.clone()).unwrap();
+        assert_eq!(signed.input[0].witness, witness);
+        assert!(tx.input[0].witness.is_empty());
+        assert_eq!(signed.compute_txid(), tx.compute_txid());
+        assert_ne!(signed.compute_wtxid(), tx.compute_wtxid());
+
+        assert_eq!(
+            tx.with_input_witness(1, witness),
+            Err(InputsIndexError(IndexOutOfBoundsError { index: 1, length: 1 }))
+        );
+    }
+
+    #[test]
+    fn txout_is_dust() {
+        let script_pubkey = ScriptBuf::new_p2wpkh(WPubkeyHash::from_byte_array([0x01; 20]));
+        let mut txout = TxOut::minimal_non_dust(script_pubkey.clone());
+        assert_eq!(txout.value, Amount::from_sat(294));
+        assert!(!txout.is_dust(FeeRate::DUST));
+
+        txout.value = Amount::from_sat(293);
+        assert!(txout.is_dust(FeeRate::DUST));
+        assert!(!txout.is_dust(FeeRate::BROADCAST_MIN));
+
+        let op_return = TxOut { value: Amount::ZERO, script_pubkey: ScriptBuf::new_op_return([]) };
+        assert!(!op_return.is_dust(FeeRate::DUST));
+    }
+
+    #[test]
+    fn outpoint_null() {
+        assert!(OutPoint::COINBASE.is_null());
+        assert_eq!(OutPoint::COINBASE, OutPoint::null());
+        assert_eq!(OutPoint::COINBASE.txid, Txid::all_zeros());
+        assert_eq!(OutPoint::COINBASE.vout, u32::MAX);
+
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        assert!(!tx.input[0].previous_output.is_null());
+        assert!(!OutPoint { txid: Txid::all_zeros(), vout: 0 }.is_null());
+        assert!(!tx.is_coinbase());
+    }
+
+    #[test]
+    fn all_zeros() {
+        const NULL: Txid = Txid::all_zeros();
+        assert!(NULL.is_all_zeros());
+        assert!(OutPoint::COINBASE.txid.is_all_zeros());
+        assert!(Wtxid::all_zeros().is_all_zeros());
+
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        assert!(!tx.compute_txid().is_all_zeros());
+        assert!(!tx.compute_wtxid().is_all_zeros());
+    }
+
     #[test]
     fn outpoint() {
         assert_eq!(OutPoint::from_str("i don't care"), Err(ParseOutPointError::Format));
@@ -1744,6 +2021,67 @@ mod tests {
         assert_eq!(txin.witness.len(), 0);
     }
 
+    #[test]
+    fn merkle_leaf() {
+        use crate::constants;
+        use crate::network::Network;
+
+        let coinbase = &constants::genesis_block(Network::Bitcoin).txdata[0];
+        assert_eq!(coinbase.merkle_leaf(), coinbase.compute_txid());
+        assert!(coinbase.witness_merkle_leaf().is_all_zeros());
+
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        assert_eq!(tx.merkle_leaf(), tx.compute_txid());
+        assert_eq!(tx.witness_merkle_leaf(), tx.compute_wtxid());
+    }
+
+    #[test]
+    fn witness_count_mismatch() {
+        // Every input carries its own witness so the counts can not differ in a `Transaction`, the
+        // decoder reads exactly one witness per input and fails if any are missing.
+        let mut tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        tx.input[0].witness.push([0x42; 4]);
+        tx.input.push(TxIn::default());
+        let mut bytes = serialize(&tx);
+        assert_eq!(deserialize::<Transaction>(&bytes).unwrap(), tx);
+
+        // Remove the empty witness of the second input, just before the lock time.
+        let lock_time_start = bytes.len() - 4;
+        assert_eq!(bytes.remove(lock_time_start - 1), 0x00);
+        assert!(deserialize::<Transaction>(&bytes).is_err());
+    }
+
+    #[test]
+    fn bip34_block_height() {
+        let coinbase = |script_sig: &[u8]| Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::COINBASE,
+                script_sig: ScriptBuf::from_bytes(script_sig.to_vec()),
+                ..Default::default()
+            }],
+            output: vec![],
+        };
+
+        // Heights of 1, 2, 3 and 4 bytes followed by arbitrary coinbase data.
+        assert_eq!(coinbase(&hex!("01640000")).bip34_block_height(), Ok(100));
+        assert_eq!(coinbase(&hex!("02ff00")).bip34_block_height(), Ok(255));
+        assert_eq!(coinbase(&hex!("03a0860100")).bip34_block_height(), Ok(100_000));
+        assert_eq!(coinbase(&hex!("0400e1f505")).bip34_block_height(), Ok(100_000_000));
+        // Small heights use the push number opcodes.
+        assert_eq!(
+            coinbase(&[crate::opcodes::all::OP_PUSHNUM_16.to_u8()]).bip34_block_height(),
+            Ok(16)
+        );
+
+        assert_eq!(coinbase(&hex!("0185")).bip34_block_height(), Err(Bip34Error::NegativeHeight));
+        assert_eq!(coinbase(&[]).bip34_block_height(), Err(Bip34Error::NotPresent));
+
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        assert_eq!(tx.bip34_block_height(), Err(Bip34Error::NotPresent));
+    }
+
     #[test]
     fn is_coinbase() {
         use crate::constants;
@@ -1800,13 +2138,7 @@ mod tests {
 
     #[test]
     fn segwit_transaction() {
-        let tx_bytes = hex!(
-            "02000000000101595895ea20179de87052b4046dfe6fd515860505d6511a9004cf12a1f93cac7c01000000\
-            00ffffffff01deb807000000000017a9140f3444e271620c736808aa7b33e370bd87cb5a078702483045022\
-            100fb60dad8df4af2841adc0346638c16d0b8035f5e3f3753b88db122e70c79f9370220756e6633b17fd271\
-            0e626347d28d60b0a2d6cbb41de51740644b9fb3ba7751040121028fa937ca8cba2197a37c007176ed89410\
-            55d3bcb8627d085e94553e62f057dcc00000000"
-        );
+        let tx_bytes = hex!(SEGWIT_TX);
         let tx: Result<Transaction, _> = deserialize(&tx_bytes);
         assert!(tx.is_ok());
         let realtx = tx.unwrap();
@@ -1847,6 +2179,205 @@ mod tests {
         assert_eq!(tx_without_witness.total_size(), expected_strippedsize);
     }
 
+    #[test]
+    fn without_witnesses() {
+        let tx_bytes = hex!(SEGWIT_TX);
+        let tx: Transaction = deserialize(&tx_bytes).unwrap();
+        let stripped = tx.without_witnesses();
+
+        assert!(stripped.input.iter().all(|txin| txin.witness.is_empty()));
+        assert_eq!(stripped.compute_txid(), tx.compute_txid());
+        assert_eq!(Txid::from_raw_hash(stripped.compute_wtxid().to_raw_hash()), tx.compute_txid());
+        assert_eq!(serialize(&stripped).len(), tx.base_size());
+
+        let txid = sha256d::Hash::hash(&serialize(&stripped));
+        assert_eq!(
+            format!("{:x}", Txid::from_raw_hash(txid)),
+            "f5864806e3565c34d1b41e716f72609d00b55ea5eac5b924c9719a842ef42206"
+        );
+    }
+
+    #[test]
+    fn sizes_hand_computed() {
+        // P2SH-P2WPKH spend, one input and one output.
+        let tx_bytes = hex!(SEGWIT_TX);
+        let tx: Transaction = deserialize(&tx_bytes).unwrap();
+
+        // version + input count + (outpoint + empty script sig + sequence)
+        // + output count + (value + script pubkey length + script pubkey) + lock time
+        let base_size = 4 + 1 + (36 + 1 + 4) + 1 + (8 + 1 + 23) + 4;
+        // marker + flag + element count + (length + signature) + (length + public key)
+        let witness_size = 2 + 1 + (1 + 72) + (1 + 33);
+
+        assert_eq!(tx.base_size(), base_size);
+        assert_eq!(tx.total_size(), base_size + witness_size);
+        assert_eq!(tx.total_size(), tx_bytes.len());
+        assert_eq!(tx.weight(), Weight::from_wu((base_size * 4 + witness_size) as u64));
+        assert_eq!(tx.weight(), Weight::from_wu(442));
+        // 442 / 4 = 110.5, rounded up.
+        assert_eq!(tx.vsize(), 111);
+    }
+
+    #[test]
+    fn minimum_fee() {
+        let tx_bytes = hex!(SEGWIT_TX);
+        let tx: Transaction = deserialize(&tx_bytes).unwrap();
+        // The weight is not a multiple of 4 so the vsize is rounded up.
+        assert_eq!(tx.weight(), Weight::from_wu(442));
+        assert_eq!(tx.vsize(), 111);
+
+        let fee_rate = FeeRate::from_sat_per_vb(10).unwrap();
+        assert_eq!(tx.minimum_fee(fee_rate), Some(Amount::from_sat(1110)));
+        // Computing the fee from the weight rounds differently.
+        assert_eq!(fee_rate.fee_wu(tx.weight()), Some(Amount::from_sat(1105)));
+
+        // 1 sat/kwu, the fee is rounded up.
+        let fee_rate = FeeRate::from_sat_per_kwu(1);
+        assert_eq!(tx.minimum_fee(fee_rate), Some(Amount::from_sat(1)));
+
+        assert_eq!(tx.minimum_fee(FeeRate::ZERO), Some(Amount::ZERO));
+        assert_eq!(tx.minimum_fee(FeeRate::MAX), None);
+    }
+
+    #[test]
+    fn has_witness() {
+        let tx_bytes = hex!(SEGWIT_TX);
+        let segwit_tx: Transaction = deserialize(&tx_bytes).unwrap();
+        assert!(segwit_tx.has_witness());
+        assert!(!segwit_tx.without_witnesses().has_witness());
+
+        let legacy_tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        assert!(!legacy_tx.has_witness());
+
+        // No inputs, still serialized using the segwit format.
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        };
+        assert!(!tx.has_witness());
+    }
+
+    #[test]
+    fn non_minimal_varint_rejected() {
+        // Consensus requires compact sizes to be minimally encoded, the decoder is always strict.
+        let mut bytes = hex!(SOME_TX);
+        assert_eq!(bytes[4], 0x01); // Input count.
+        bytes.splice(4..5, [0xfd, 0x01, 0x00]);
+
+        let err = deserialize::<Transaction>(&bytes).unwrap_err();
+        assert!(matches!(err, encode::Error::NonMinimalVarInt));
+    }
+
+    #[test]
+    fn find_output_to() {
+        use crate::Network;
+
+        let mut tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        let address = Address::from_script(&tx.output[0].script_pubkey, Network::Bitcoin).unwrap();
+        assert_eq!(tx.find_output_to(&address), Some((0, &tx.output[0])));
+
+        let other = "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw"
+            .parse::<Address<_>>()
+            .unwrap()
+            .assume_checked();
+        assert_eq!(tx.find_output_to(&other), None);
+
+        tx.output.insert(
+            0,
+            TxOut { value: Amount::from_sat(1_000), script_pubkey: other.script_pubkey() },
+        );
+        assert_eq!(tx.find_output_to(&other), Some((0, &tx.output[0])));
+        assert_eq!(tx.find_output_to(&address), Some((1, &tx.output[1])));
+    }
+
+    #[test]
+    fn weight_breakdown() {
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        let breakdown = tx.weight_breakdown();
+        assert_eq!(breakdown.total(), tx.weight());
+        assert_eq!(breakdown.overhead, Weight::from_wu(40));
+        assert_eq!(breakdown.inputs, tx.input[0].legacy_weight());
+        assert_eq!(breakdown.outputs, tx.output[0].weight());
+        assert_eq!(breakdown.witness, Weight::ZERO);
+
+        let tx_bytes = hex!(SEGWIT_TX);
+        let tx: Transaction = deserialize(&tx_bytes).unwrap();
+        let breakdown = tx.weight_breakdown();
+        assert_eq!(breakdown.total(), tx.weight());
+        // Marker, flag and the witness of the single input.
+        assert_eq!(breakdown.witness.to_wu(), 2 + tx.input[0].witness.size() as u64);
+
+        // Transactions without inputs use the segwit serialization.
+        let empty = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        };
+        assert_eq!(empty.weight_breakdown().total(), empty.weight());
+    }
+
+    #[test]
+    fn total_output_value() {
+        let mut tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        assert_eq!(tx.output.len(), 1);
+        let value = tx.output[0].value;
+        assert_eq!(tx.total_output_value(), Some(value));
+
+        tx.output.push(TxOut { value: Amount::from_sat(1_000), script_pubkey: ScriptBuf::new() });
+        assert_eq!(tx.total_output_value(), Some(value + Amount::from_sat(1_000)));
+
+        tx.output.push(TxOut { value: Amount::MAX, script_pubkey: ScriptBuf::new() });
+        assert_eq!(tx.total_output_value(), None);
+
+        tx.output.clear();
+        assert_eq!(tx.total_output_value(), Some(Amount::ZERO));
+    }
+
+    #[test]
+    fn encode_inputs_outputs() {
+        let tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        let full = serialize(&tx);
+
+        let mut inputs = Vec::new();
+        let inputs_len = tx.encode_inputs(&mut inputs).unwrap();
+        assert_eq!(inputs_len, inputs.len());
+        let mut outputs = Vec::new();
+        let outputs_len = tx.encode_outputs(&mut outputs).unwrap();
+        assert_eq!(outputs_len, outputs.len());
+
+        // Legacy serialization is version, inputs, outputs and lock time.
+        assert_eq!(&full[4..4 + inputs_len], &inputs[..]);
+        assert_eq!(&full[4 + inputs_len..full.len() - 4], &outputs[..]);
+
+        // Segwit serialization has the marker and flag before the inputs.
+        let tx_bytes = hex!(SEGWIT_TX);
+        let tx: Transaction = deserialize(&tx_bytes).unwrap();
+        let mut inputs = Vec::new();
+        let inputs_len = tx.encode_inputs(&mut inputs).unwrap();
+        let mut outputs = Vec::new();
+        let outputs_len = tx.encode_outputs(&mut outputs).unwrap();
+        assert_eq!(&tx_bytes[6..6 + inputs_len], &inputs[..]);
+        assert_eq!(&tx_bytes[6 + inputs_len..6 + inputs_len + outputs_len], &outputs[..]);
+    }
+
+    #[test]
+    fn is_finalized() {
+        let legacy_tx: Transaction = deserialize(&hex!(SOME_TX)).unwrap();
+        assert!(legacy_tx.is_finalized());
+
+        let tx_bytes = hex!(SEGWIT_TX);
+        let segwit_tx: Transaction = deserialize(&tx_bytes).unwrap();
+        assert!(segwit_tx.is_finalized());
+        assert!(!segwit_tx.without_witnesses().is_finalized());
+
+        let mut tx = legacy_tx.clone();
+        tx.input.push(TxIn::default());
+        assert!(!tx.is_finalized());
+    }
+
     // We temporarily abuse `Transaction` for testing consensus serde adapter.
     #[cfg(feature = "serde")]
     #[test]
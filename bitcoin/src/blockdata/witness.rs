@@ -10,7 +10,7 @@ use core::ops::Index;
 use io::{BufRead, Write};
 
 use crate::consensus::encode::{Error, MAX_VEC_SIZE};
-use crate::consensus::{Decodable, Encodable, WriteExt};
+use crate::consensus::{Decodable, Encodable, EncodedLen, WriteExt};
 use crate::crypto::ecdsa;
 use crate::prelude::Vec;
 use crate::taproot::{self, TAPROOT_ANNEX_PREFIX};
@@ -236,6 +236,11 @@ impl Encodable for Witness {
     }
 }
 
+impl EncodedLen for Witness {
+    #[inline]
+    fn encoded_len(&self) -> usize { self.size() }
+}
+
 impl Witness {
     /// Creates a new empty [`Witness`].
     #[inline]
@@ -243,6 +248,16 @@ impl Witness {
         Witness { content: Vec::new(), witness_elements: 0, indices_start: 0 }
     }
 
+    /// Creates a new empty [`Witness`] with space for `witness_elements` elements.
+    ///
+    /// `element_bytes` is the total length of all the elements, excluding their length prefixes.
+    /// If all elements are shorter than 253 bytes pushing them will not reallocate.
+    pub fn with_capacity(witness_elements: usize, element_bytes: usize) -> Self {
+        // Each element has (at least) a one byte length prefix and a four byte index.
+        let capacity = element_bytes + witness_elements * 5;
+        Witness { content: Vec::with_capacity(capacity), witness_elements: 0, indices_start: 0 }
+    }
+
     /// Creates a witness required to spend a P2WPKH output.
     ///
     /// The witness will be made up of the DER encoded signature + sighash_type followed by the
@@ -264,6 +279,9 @@ impl Witness {
     }
 
     /// Creates a [`Witness`] object from a slice of bytes slices where each slice is a witness item.
+    ///
+    /// The size of the witness is computed up front so this only allocates once, when all the
+    /// elements are known this is therefore more efficient than pushing them one by one.
     pub fn from_slice<T: AsRef<[u8]>>(slice: &[T]) -> Self {
         let witness_elements = slice.len();
         let index_size = witness_elements * 4;
@@ -328,7 +346,19 @@ impl Witness {
         self.indices_start = 0;
     }
 
-    /// Push a new element on the witness, requires an allocation.
+    /// Reserves capacity for at least `additional_elements` more elements.
+    ///
+    /// `additional_bytes` is the total length of the new elements, excluding their length
+    /// prefixes. See [`Witness::with_capacity`].
+    pub fn reserve(&mut self, additional_elements: usize, additional_bytes: usize) {
+        self.content.reserve(additional_bytes + additional_elements * 5);
+    }
+
+    /// Push a new element on the witness.
+    ///
+    /// The element is pushed as is, the length prefix is added when the witness is serialized.
+    /// This reallocates if there is not enough spare capacity, use [`Witness::reserve`] to avoid
+    /// reallocating for every push.
     pub fn push<T: AsRef<[u8]>>(&mut self, new_element: T) {
         self.push_slice(new_element.as_ref());
     }
@@ -395,6 +425,25 @@ impl Witness {
         self.element_at(pos)
     }
 
+    /// Returns true if the witnesses are equal after removing any trailing empty elements.
+    ///
+    /// Empty elements are still pushed onto the stack when the input is validated, so two
+    /// witnesses equal by this method are not necessarily equivalent under consensus rules. This
+    /// is useful when comparing witnesses produced by different signers.
+    pub fn eq_ignoring_trailing_empty(&self, other: &Witness) -> bool {
+        fn trimmed_len(witness: &Witness) -> usize {
+            witness
+                .iter()
+                .enumerate()
+                .filter(|(_, elem)| !elem.is_empty())
+                .last()
+                .map_or(0, |(i, _)| i + 1)
+        }
+
+        let len = trimmed_len(self);
+        len == trimmed_len(other) && self.iter().take(len).eq(other.iter().take(len))
+    }
+
     /// Get Tapscript following BIP341 rules regarding accounting for an annex.
     ///
     /// This does not guarantee that this represents a P2TR [`Witness`]. It
@@ -422,6 +471,77 @@ impl Witness {
             .and_then(|script_pos_from_last| self.nth(len - script_pos_from_last))
             .map(Script::from_bytes)
     }
+
+    /// Checks the consistency of the internal representation of the witness.
+    ///
+    /// The elements must be stored contiguously, each one starting where the previous one ends,
+    /// and be followed by exactly one index entry per element. This always holds for witnesses
+    /// created using the public API, it is intended to be used in tests and fuzzing.
+    pub fn check_invariants(&self) -> Result<(), InvariantError> {
+        let expected_len = self
+            .witness_elements
+            .checked_mul(4)
+            .and_then(|indices_len| indices_len.checked_add(self.indices_start));
+        if expected_len != Some(self.content.len()) {
+            return Err(InvariantError::InvalidLength);
+        }
+
+        let mut expected_pos = 0;
+        for i in 0..self.witness_elements {
+            let pos = decode_cursor(&self.content, self.indices_start, i)
+                .expect("length of index area checked above");
+            if pos != expected_pos {
+                return Err(InvariantError::InvalidElement(i));
+            }
+            let varint = VarInt::consensus_decode(&mut &self.content[pos..self.indices_start])
+                .map_err(|_| InvariantError::InvalidElement(i))?;
+            expected_pos = (pos + varint.size())
+                .checked_add(varint.0 as usize)
+                .filter(|&end| end <= self.indices_start)
+                .ok_or(InvariantError::InvalidElement(i))?;
+        }
+
+        if expected_pos != self.indices_start {
+            return Err(InvariantError::InvalidLength);
+        }
+        Ok(())
+    }
+}
+
+/// The internal representation of a [`Witness`] is inconsistent.
+///
+/// Returned by [`Witness::check_invariants`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvariantError {
+    /// The length of the content does not match the number of elements.
+    InvalidLength,
+    /// The element at this index is not where its index entry points to or overruns the content.
+    InvalidElement(usize),
+}
+
+internals::impl_from_infallible!(InvariantError);
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use InvariantError::*;
+
+        match *self {
+            InvalidLength => f.write_str("witness content length does not match its elements"),
+            InvalidElement(i) => write!(f, "witness element {} is inconsistent", i),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use InvariantError::*;
+
+        match *self {
+            InvalidLength | InvalidElement(_) => None,
+        }
+    }
 }
 
 impl Index<usize> for Witness {
@@ -586,6 +706,73 @@ mod test {
         println!("{:?}", witness);
     }
 
+    #[test]
+    fn eq_ignoring_trailing_empty() {
+        let witness = Witness::from_slice(&[vec![0x01; 72], vec![], vec![0x02; 33]]);
+        let mut trailing = witness.clone();
+        trailing.push([]);
+        trailing.push([]);
+        assert_ne!(witness, trailing);
+        assert!(witness.eq_ignoring_trailing_empty(&trailing));
+        assert!(trailing.eq_ignoring_trailing_empty(&witness));
+
+        // Empty elements in the middle are significant.
+        let no_middle = Witness::from_slice(&[vec![0x01; 72], vec![0x02; 33]]);
+        assert!(!witness.eq_ignoring_trailing_empty(&no_middle));
+
+        let mut different = witness.clone();
+        different.push([0x03]);
+        assert!(!witness.eq_ignoring_trailing_empty(&different));
+
+        let empty = Witness::from_slice(&[Vec::<u8>::new()]);
+        assert!(empty.eq_ignoring_trailing_empty(&Witness::new()));
+    }
+
+    #[test]
+    fn check_invariants() {
+        let mut witness = Witness::new();
+        assert_eq!(witness.check_invariants(), Ok(()));
+        witness.push([0x01; 72]);
+        witness.push([]);
+        witness.push([0x02; 300]);
+        assert_eq!(witness.check_invariants(), Ok(()));
+
+        let deserialized: Witness = deserialize(&serialize(&witness)).unwrap();
+        assert_eq!(deserialized.check_invariants(), Ok(()));
+
+        // Index area without any element content.
+        let witness = Witness {
+            witness_elements: 1,
+            content: append_u32_vec(vec![], &[0]),
+            indices_start: 2,
+        };
+        assert_eq!(witness.check_invariants(), Err(InvariantError::InvalidLength));
+
+        // Second index points into the first element.
+        let witness = Witness {
+            witness_elements: 2,
+            content: append_u32_vec(vec![1u8, 0, 1, 0], &[0, 1]),
+            indices_start: 4,
+        };
+        assert_eq!(witness.check_invariants(), Err(InvariantError::InvalidElement(1)));
+
+        // Element length overruns the content.
+        let witness = Witness {
+            witness_elements: 1,
+            content: append_u32_vec(vec![5u8, 0], &[0]),
+            indices_start: 2,
+        };
+        assert_eq!(witness.check_invariants(), Err(InvariantError::InvalidElement(0)));
+
+        // Trailing bytes after the last element.
+        let witness = Witness {
+            witness_elements: 1,
+            content: append_u32_vec(vec![1u8, 0, 0], &[0]),
+            indices_start: 3,
+        };
+        assert_eq!(witness.check_invariants(), Err(InvariantError::InvalidLength));
+    }
+
     #[test]
     fn test_push() {
         let mut witness = Witness::default();
@@ -642,6 +829,45 @@ mod test {
         assert_eq!(&witness[2], &[4u8, 5u8][..]);
     }
 
+    #[test]
+    fn from_slice_equals_push() {
+        let elements: [&[u8]; 4] = [&[], &[0x01], &[0xab; 100], &[0xcd; 300]];
+
+        let mut pushed = Witness::new();
+        for element in elements {
+            pushed.push(element);
+        }
+        let witness = Witness::from_slice(&elements);
+        assert_eq!(witness, pushed);
+        assert_eq!(serialize(&witness), serialize(&pushed));
+        assert_eq!(witness.to_bytes(), elements.iter().map(|e| e.to_vec()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn with_capacity() {
+        let elements: [&[u8]; 3] = [&[0x01; 72], &[0x02; 33], &[]];
+
+        let mut witness = Witness::with_capacity(3, 72 + 33);
+        let capacity = witness.content.capacity();
+        for element in elements {
+            witness.push(element);
+        }
+        assert_eq!(witness.content.capacity(), capacity);
+        assert_eq!(witness, Witness::from_slice(&elements));
+    }
+
+    #[test]
+    fn reserve() {
+        let mut witness = Witness::from_slice(&[[0x01; 10]]);
+        witness.reserve(2, 72 + 33);
+        let capacity = witness.content.capacity();
+        witness.push([0x02; 72]);
+        witness.push([0x03; 33]);
+        assert_eq!(witness.content.capacity(), capacity);
+        assert_eq!(witness.len(), 3);
+        assert_eq!(witness.last(), Some(&[0x03; 33][..]));
+    }
+
     #[test]
     fn test_iter_len() {
         let mut witness = Witness::default();
@@ -671,6 +897,23 @@ mod test {
         assert_eq!(witness.to_vec(), expected_witness);
     }
 
+    #[test]
+    fn p2wpkh() {
+        let sig_bytes =
+            hex!("304402207c800d698f4b0298c5aac830b822f011bb02df41eb114ade9a6702f364d5e39c0220366900d2a60cab903e77ef7dd415d46509b1f78ac78906e3296f495aa1b1b541");
+        let signature = secp256k1::ecdsa::Signature::from_der(&sig_bytes).unwrap();
+        let signature = crate::ecdsa::Signature { signature, sighash_type: EcdsaSighashType::All };
+        let pubkey = "026c468be64d22761c30cd2f12cbc7de255d592d7904b1bab07236897cc4c2e766"
+            .parse::<crate::CompressedPublicKey>()
+            .unwrap();
+
+        let witness = Witness::p2wpkh(signature, pubkey.0);
+        assert_eq!(witness.len(), 2);
+        assert_eq!(witness.nth(0).unwrap(), signature.to_vec());
+        assert_eq!(witness.nth(0).unwrap().last(), Some(&0x01));
+        assert_eq!(witness.nth(1).unwrap(), pubkey.to_bytes());
+    }
+
     #[test]
     fn test_witness() {
         let w0 = hex!("03d2e15674941bad4a996372cb87e1856d3652606d98562fe39c5e9e7e413f2105");
@@ -21,10 +21,11 @@ use hex::error::{InvalidCharError, OddLengthStringError};
 use internals::write_err;
 use io::{BufRead, Cursor, Read, Write};
 
+use crate::address::{self, DecodeAddressesError};
 use crate::bip152::{PrefilledTransaction, ShortId};
 use crate::bip158::{FilterHash, FilterHeader};
 use crate::block::{self, BlockHash};
-use crate::consensus::{DecodeError, IterReader};
+use crate::consensus::{DecodeError, IterReader, Params};
 use crate::merkle_tree::TxMerkleNode;
 #[cfg(feature = "std")]
 use crate::p2p::{
@@ -32,6 +33,7 @@ use crate::p2p::{
     message_blockdata::Inventory,
 };
 use crate::prelude::{Box, Cow, DisplayHex, rc, String, sync, Vec};
+use crate::script::ScriptBuf;
 use crate::taproot::TapLeafHash;
 use crate::transaction::{Transaction, TxIn, TxOut};
 
@@ -141,6 +143,47 @@ impl From<OddLengthStringError> for FromHexError {
     fn from(e: OddLengthStringError) -> Self { Self::OddLengthString(e) }
 }
 
+/// Error returned by [`deserialize_prefix`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PrefixDecodeError {
+    /// The data ended before a complete object could be decoded.
+    Incomplete {
+        /// The minimum number of additional bytes required to make progress.
+        ///
+        /// More bytes may be required once these have been supplied.
+        needed: usize,
+    },
+    /// The data is not a valid encoding of the object.
+    Invalid(Error),
+}
+
+internals::impl_from_infallible!(PrefixDecodeError);
+
+impl fmt::Display for PrefixDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use PrefixDecodeError::*;
+
+        match *self {
+            Incomplete { needed } =>
+                write!(f, "incomplete data: at least {} more bytes required", needed),
+            Invalid(ref e) => write_err!(f, "invalid consensus encoding"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PrefixDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use PrefixDecodeError::*;
+
+        match *self {
+            Incomplete { .. } => None,
+            Invalid(ref e) => Some(e),
+        }
+    }
+}
+
 /// Encodes an object into a vector.
 pub fn serialize<T: Encodable + ?Sized>(data: &T) -> Vec<u8> {
     let mut encoder = Vec::new();
@@ -185,6 +228,88 @@ pub fn deserialize_partial<T: Decodable>(data: &[u8]) -> Result<(T, usize), Erro
     Ok((rv, consumed))
 }
 
+/// Deserializes an object from the start of `data`, distinguishing truncated from invalid data.
+///
+/// Like [`deserialize_partial`] this does not require `data` to be consumed entirely, on success
+/// the number of bytes consumed is returned along with the object. If `data` ends part way through
+/// the object [`PrefixDecodeError::Incomplete`] is returned, this is useful when decoding from a
+/// stream where the caller can wait for more bytes to arrive instead of treating the data as
+/// malformed.
+pub fn deserialize_prefix<T: Decodable>(data: &[u8]) -> Result<(T, usize), PrefixDecodeError> {
+    let mut decoder = EofTrackingReader { data, pos: 0, needed: None };
+    let result = Decodable::consensus_decode_from_finite_reader(&mut decoder);
+    match (result, decoder.needed) {
+        (Ok(rv), _) => Ok((rv, decoder.pos)),
+        (Err(Error::Io(ref e)), Some(needed)) if e.kind() == io::ErrorKind::UnexpectedEof =>
+            Err(PrefixDecodeError::Incomplete { needed }),
+        (Err(e), _) => Err(PrefixDecodeError::Invalid(e)),
+    }
+}
+
+/// Encodes a list of addresses as the consensus encoding of their script pubkeys.
+///
+/// The list is prefixed with its length as a [`VarInt`] like consensus encoded vectors. The network
+/// of the addresses is not encoded, decode the list with [`decode_addresses`].
+pub fn encode_addresses<W: Write + ?Sized>(
+    addresses: &[address::Address],
+    w: &mut W,
+) -> Result<usize, io::Error> {
+    let mut len = VarInt::from(addresses.len()).consensus_encode(w)?;
+    for address in addresses {
+        len += address.script_pubkey().consensus_encode(w)?;
+    }
+    Ok(len)
+}
+
+/// Decodes a list of addresses encoded with [`encode_addresses`].
+///
+/// The addresses are reconstructed for the network given by `params`, see
+/// [`Address::from_script`](address::Address::from_script).
+pub fn decode_addresses<R: BufRead + ?Sized>(
+    r: &mut R,
+    params: impl AsRef<Params>,
+) -> Result<Vec<address::Address>, DecodeAddressesError> {
+    let len = VarInt::consensus_decode(r)?.0;
+    // Do not trust the length prefix for the allocation, each script is at least one byte.
+    let mut addresses = Vec::new();
+    for i in 0..len {
+        let script_pubkey = ScriptBuf::consensus_decode(r)?;
+        let address = address::Address::from_script(&script_pubkey, params.as_ref())
+            .map_err(|error| DecodeAddressesError::FromScript { index: i as usize, error })?;
+        addresses.push(address);
+    }
+    Ok(addresses)
+}
+
+/// Slice reader that records how many bytes were requested once the end of the data is reached.
+struct EofTrackingReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    needed: Option<usize>,
+}
+
+impl<'a> Read for EofTrackingReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        let remaining = &self.data[self.pos..];
+        if remaining.is_empty() && !buf.is_empty() {
+            self.needed = Some(buf.len());
+            return Ok(0);
+        }
+        let len = core::cmp::min(remaining.len(), buf.len());
+        buf[..len].copy_from_slice(&remaining[..len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+impl<'a> BufRead for EofTrackingReader<'a> {
+    fn fill_buf(&mut self) -> Result<&[u8], io::Error> { Ok(&self.data[self.pos..]) }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos = core::cmp::min(self.pos + amount, self.data.len());
+    }
+}
+
 /// Extensions of `Write` to encode data as per Bitcoin consensus.
 pub trait WriteExt: Write {
     /// Outputs a 64-bit unsigned integer.
@@ -319,6 +444,12 @@ pub trait Encodable {
     fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error>;
 }
 
+/// Data whose consensus encoded length can be computed without encoding it.
+pub trait EncodedLen: Encodable {
+    /// Returns the number of bytes [`Encodable::consensus_encode`] writes for this object.
+    fn encoded_len(&self) -> usize;
+}
+
 /// Data which can be encoded in a consensus-consistent way.
 pub trait Decodable: Sized {
     /// Decode `Self` from a size-limited reader.
@@ -1291,12 +1422,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn encoded_len() {
+        use crate::{ScriptBuf, Witness};
+
+        fn check<T: EncodedLen>(x: &T) { assert_eq!(x.encoded_len(), serialize(x).len()) }
+
+        let hex = include_str!("../../tests/data/previous_tx_0_hex"); // An arbitrary transaction.
+        let mut tx = deserialize_hex::<Transaction>(hex).unwrap();
+        check(&tx);
+        check(&tx.input[0]);
+        check(&tx.output[0]);
+        check(&tx.input[0].script_sig);
+        check(&tx.output[0].script_pubkey);
+        let spk = &tx.output[0].script_pubkey;
+        assert_eq!(spk.as_script().encoded_len(), spk.encoded_len());
+
+        check(&ScriptBuf::new());
+        check(&ScriptBuf::from_bytes(vec![0x51; 300]));
+        check(&Witness::new());
+
+        let witness = Witness::from_slice(&[vec![0x01; 72], vec![], vec![0x02; 300]]);
+        check(&witness);
+        tx.input[0].witness = witness;
+        check(&tx);
+        check(&tx.input[0]);
+
+        tx.input.clear();
+        check(&tx);
+    }
+
     #[test]
     fn deserialize_tx_hex() {
         let hex = include_str!("../../tests/data/previous_tx_0_hex"); // An arbitrary transaction.
         assert!(deserialize_hex::<Transaction>(hex).is_ok())
     }
 
+    #[test]
+    fn deserialize_prefix_truncated_tx() {
+        let hex = include_str!("../../tests/data/previous_tx_0_hex"); // An arbitrary transaction.
+        let tx = deserialize_hex::<Transaction>(hex).unwrap();
+        let data = serialize(&tx);
+
+        assert_eq!(deserialize_prefix::<Transaction>(&data).unwrap(), (tx, data.len()));
+
+        // Lock time is the last field, cut it in half.
+        let truncated = &data[..data.len() - 2];
+        assert!(matches!(
+            deserialize_prefix::<Transaction>(truncated).unwrap_err(),
+            PrefixDecodeError::Incomplete { needed: 2 }
+        ));
+        assert!(matches!(
+            deserialize_prefix::<Transaction>(&[]).unwrap_err(),
+            PrefixDecodeError::Incomplete { needed: 4 }
+        ));
+    }
+
+    #[test]
+    fn deserialize_prefix_invalid_tx() {
+        // Version 2, segwit marker followed by an unsupported flag.
+        let data = [0x02, 0x00, 0x00, 0x00, 0x00, 0x02];
+        assert!(matches!(
+            deserialize_prefix::<Transaction>(&data).unwrap_err(),
+            PrefixDecodeError::Invalid(Error::UnsupportedSegwitFlag(2))
+        ));
+    }
+
     #[test]
     fn deserialize_tx_hex_too_many_bytes() {
         use crate::consensus::DecodeError;
@@ -1308,4 +1499,68 @@ mod tests {
             FromHexError::Decode(DecodeError::TooManyBytes)
         ));
     }
+
+    #[test]
+    fn encode_decode_addresses() {
+        use crate::network::Network::{Bitcoin, Testnet};
+
+        let addresses = [
+            "1QJVDzdqb1VpbDK7uDeyVXy9mR27CJiyhY",
+            "33iFwdLuRpW1uK1RTRqsoi8rR4NpDzk66k",
+            "bc1qvzvkjn4q3nszqxrv3nraga2r822xjty3ykvkuw",
+            "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej",
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+        ]
+        .iter()
+        .map(|s| s.parse::<address::Address<_>>().unwrap().require_network(Bitcoin).unwrap())
+        .collect::<Vec<_>>();
+
+        let mut buf = Vec::new();
+        let len = encode_addresses(&addresses, &mut buf).unwrap();
+        assert_eq!(len, buf.len());
+        let size = 1 + addresses.iter().map(|a| 1 + a.script_pubkey_len()).sum::<usize>();
+        assert_eq!(buf.len(), size);
+
+        let decoded = decode_addresses(&mut buf.as_slice(), Bitcoin).unwrap();
+        assert_eq!(decoded, addresses);
+
+        // The network is not encoded.
+        let decoded = decode_addresses(&mut buf.as_slice(), Testnet).unwrap();
+        for (decoded, address) in decoded.iter().zip(&addresses) {
+            assert!(decoded.same_script_pubkey(address));
+            assert!(decoded.as_unchecked().is_valid_for_network(Testnet));
+        }
+
+        let mut buf = Vec::new();
+        encode_addresses(&[], &mut buf).unwrap();
+        assert_eq!(buf, [0]);
+        assert_eq!(decode_addresses(&mut buf.as_slice(), Bitcoin).unwrap(), []);
+    }
+
+    #[test]
+    fn decode_addresses_error() {
+        use crate::address::FromScriptError;
+        use crate::network::Network::Bitcoin;
+        use crate::PubkeyHash;
+
+        let op_return = ScriptBuf::new_op_return([0x42; 4]);
+        let p2pkh = ScriptBuf::new_p2pkh(PubkeyHash::from_byte_array([0x42; 20]));
+        let mut buf = vec![2];
+        p2pkh.consensus_encode(&mut buf).unwrap();
+        op_return.consensus_encode(&mut buf).unwrap();
+        match decode_addresses(&mut buf.as_slice(), Bitcoin) {
+            Err(DecodeAddressesError::FromScript { index, error }) => {
+                assert_eq!(index, 1);
+                assert_eq!(error, FromScriptError::UnrecognizedScript);
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        // Truncated.
+        buf.pop();
+        assert!(matches!(
+            decode_addresses(&mut buf.as_slice(), Bitcoin),
+            Err(DecodeAddressesError::Decode(_))
+        ));
+    }
 }
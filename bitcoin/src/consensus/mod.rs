@@ -22,7 +22,10 @@ use crate::consensus;
 #[rustfmt::skip]                // Keep public re-exports separate.
 #[doc(inline)]
 pub use self::{
-    encode::{deserialize, deserialize_partial, serialize, Decodable, Encodable, ReadExt, WriteExt},
+    encode::{
+        decode_addresses, deserialize, deserialize_partial, encode_addresses, serialize,
+        Decodable, Encodable, EncodedLen, ReadExt, WriteExt,
+    },
     params::Params,
 };
 
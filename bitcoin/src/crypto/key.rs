@@ -371,6 +371,10 @@ impl TryFrom<PublicKey> for CompressedPublicKey {
     }
 }
 
+impl From<secp256k1::PublicKey> for CompressedPublicKey {
+    fn from(pk: secp256k1::PublicKey) -> Self { CompressedPublicKey(pk) }
+}
+
 impl From<CompressedPublicKey> for PublicKey {
     fn from(value: CompressedPublicKey) -> Self { PublicKey::new(value.0) }
 }
@@ -42,12 +42,10 @@ pub enum NetworkKind {
 // We explicitly do not provide `is_testnet`, using `!network.is_mainnet()` is less
 // ambiguous due to confusion caused by signet/testnet/regtest.
 impl NetworkKind {
-    /// Returns true if this is real mainnet bitcoin.
-    pub fn is_mainnet(&self) -> bool { *self == NetworkKind::Main }
-}
-
-impl From<Network> for NetworkKind {
-    fn from(n: Network) -> Self {
+    /// Returns the kind of network `n` is.
+    ///
+    /// This is the same as `NetworkKind::from(n)` but can be used in const contexts.
+    pub const fn from_network(n: Network) -> Self {
         use Network::*;
 
         match n {
@@ -55,6 +53,42 @@ impl From<Network> for NetworkKind {
             Testnet | Signet | Regtest => NetworkKind::Test,
         }
     }
+
+    /// Returns true if this is real mainnet bitcoin.
+    pub fn is_mainnet(&self) -> bool { *self == NetworkKind::Main }
+
+    /// Returns the [SLIP-44] coin type of this kind of network, as used in BIP-44 style
+    /// derivation paths.
+    ///
+    /// All test networks share coin type 1.
+    ///
+    /// [SLIP-44]: <https://github.com/satoshilabs/slips/blob/master/slip-0044.md>
+    pub const fn slip44_coin_type(self) -> u32 {
+        match self {
+            NetworkKind::Main => 0,
+            NetworkKind::Test => 1,
+        }
+    }
+
+    /// Returns the kind of network for a [SLIP-44] coin type, if it is a bitcoin coin type.
+    ///
+    /// Note that there is no equivalent for [BIP-21] URIs, the `bitcoin:` scheme is used for all
+    /// networks. The network of a URI is that of its address, check it with
+    /// [`Address::require_network`](crate::Address::require_network) after parsing.
+    ///
+    /// [SLIP-44]: <https://github.com/satoshilabs/slips/blob/master/slip-0044.md>
+    /// [BIP-21]: <https://github.com/bitcoin/bips/blob/master/bip-0021.mediawiki>
+    pub const fn from_slip44_coin_type(coin_type: u32) -> Option<Self> {
+        match coin_type {
+            0 => Some(NetworkKind::Main),
+            1 => Some(NetworkKind::Test),
+            _ => None,
+        }
+    }
+}
+
+impl From<Network> for NetworkKind {
+    fn from(n: Network) -> Self { NetworkKind::from_network(n) }
 }
 
 /// The cryptocurrency network to act on.
@@ -74,7 +108,18 @@ pub enum Network {
     Regtest,
 }
 
+// As for `NetworkKind` we explicitly do not provide `is_testnet`.
 impl Network {
+    /// Returns true if this is real mainnet bitcoin.
+    pub fn is_mainnet(self) -> bool { self == Network::Bitcoin }
+
+    /// Returns all the networks, in the order they are declared.
+    ///
+    /// Useful for iterating over the networks without hardcoding them.
+    pub const fn all() -> &'static [Network] {
+        &[Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest]
+    }
+
     /// Creates a `Network` from the magic bytes.
     ///
     /// # Examples
@@ -306,9 +351,57 @@ impl TryFrom<ChainHash> for Network {
 
 #[cfg(test)]
 mod tests {
-    use super::Network;
+    use super::{Network, NetworkKind};
     use crate::consensus::encode::{deserialize, serialize};
-    use crate::p2p::ServiceFlags;
+    use crate::p2p::{Magic, ServiceFlags};
+
+    #[test]
+    fn network_kind_from_network() {
+        const MAIN: NetworkKind = NetworkKind::from_network(Network::Bitcoin);
+        assert_eq!(MAIN, NetworkKind::Main);
+
+        for network in [Network::Testnet, Network::Signet, Network::Regtest] {
+            assert_eq!(NetworkKind::from_network(network), NetworkKind::Test);
+            assert_eq!(NetworkKind::from(network), NetworkKind::Test);
+        }
+    }
+
+    #[test]
+    fn is_mainnet() {
+        assert!(Network::Bitcoin.is_mainnet());
+        assert!(NetworkKind::Main.is_mainnet());
+
+        for network in [Network::Testnet, Network::Signet, Network::Regtest] {
+            assert!(!network.is_mainnet());
+            assert!(!NetworkKind::from(network).is_mainnet());
+        }
+        assert!(!NetworkKind::Test.is_mainnet());
+    }
+
+    #[test]
+    fn slip44_coin_type() {
+        for kind in [NetworkKind::Main, NetworkKind::Test] {
+            assert_eq!(NetworkKind::from_slip44_coin_type(kind.slip44_coin_type()), Some(kind));
+        }
+        assert_eq!(NetworkKind::from(Network::Bitcoin).slip44_coin_type(), 0);
+        assert_eq!(NetworkKind::from(Network::Signet).slip44_coin_type(), 1);
+        // Litecoin.
+        assert_eq!(NetworkKind::from_slip44_coin_type(2), None);
+    }
+
+    #[test]
+    fn all() {
+        let all = Network::all();
+        assert_eq!(all, [Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest]);
+        // Every network has distinct parameters.
+        for (i, network) in all.iter().enumerate() {
+            for other in &all[i + 1..] {
+                assert_ne!(network.chain_hash(), other.chain_hash());
+                assert_ne!(network.magic(), other.magic());
+            }
+            assert_eq!(Network::from_core_arg(network.to_core_arg()).unwrap(), *network);
+        }
+    }
 
     #[test]
     fn serialize_test() {
@@ -323,6 +416,19 @@ mod tests {
         assert_eq!(deserialize(&[0xfa, 0xbf, 0xb5, 0xda]).ok(), Some(Network::Regtest.magic()));
     }
 
+    #[test]
+    fn magic_round_trip() {
+        assert_eq!(Network::Bitcoin.magic(), Magic::BITCOIN);
+        assert_eq!(Network::Testnet.magic(), Magic::TESTNET);
+        assert_eq!(Network::Signet.magic(), Magic::SIGNET);
+        assert_eq!(Network::Regtest.magic(), Magic::REGTEST);
+
+        for network in Network::all() {
+            assert_eq!(Network::from_magic(network.magic()), Some(*network));
+        }
+        assert_eq!(Network::from_magic(Magic::from_bytes([0xfb, 0xc0, 0xb6, 0xdb])), None);
+    }
+
     #[test]
     fn string_test() {
         assert_eq!(Network::Bitcoin.to_string(), "bitcoin");
@@ -43,6 +43,10 @@ pub const DEFAULT_MIN_RELAY_TX_FEE: u32 = 1_000;
 /// mempools.
 pub const DEFAULT_MEMPOOL_EXPIRY: u32 = 336;
 
+/// Maximum number of bytes of data pushed in an `OP_RETURN` output for it to be relayed by most
+/// nodes on the network.
+pub const MAX_OP_RETURN_DATA_SIZE: u32 = 80;
+
 /// The virtual transaction size, as computed by default by bitcoind node.
 pub fn get_virtual_tx_size(weight: i64, n_sigops: i64) -> i64 {
     (cmp::max(weight, n_sigops * DEFAULT_BYTES_PER_SIGOP as i64) + WITNESS_SCALE_FACTOR as i64 - 1)
@@ -5,6 +5,7 @@ use honggfuzz::fuzz;
 fn do_test(data: &[u8]) {
     let w: Result<Witness, _> = deserialize(data);
     if let Ok(witness) = w {
+        witness.check_invariants().expect("deserialized witness is consistent");
         let serialized = serialize(&witness);
         assert_eq!(data, &serialized[..]);
     }
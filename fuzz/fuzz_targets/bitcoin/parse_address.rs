@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+use bitcoin::address::{Address, NetworkUnchecked};
+use bitcoin::Network;
+use honggfuzz::fuzz;
+
+fn do_test(data: &[u8]) {
+    let data_str = match std::str::from_utf8(data) {
+        Err(_) => return,
+        Ok(s) => s,
+    };
+    let addr = match Address::<NetworkUnchecked>::from_str(data_str) {
+        Ok(addr) => addr,
+        Err(_) => return,
+    };
+
+    let s = addr.assume_checked_ref().to_string();
+    let parsed = Address::<NetworkUnchecked>::from_str(&s).expect("formatted address parses");
+    assert_eq!(parsed, addr);
+    assert_eq!(parsed.assume_checked_ref().to_string(), s);
+
+    let networks = [Network::Bitcoin, Network::Testnet, Network::Signet, Network::Regtest];
+    let valid: Vec<bool> = networks.iter().map(|n| addr.is_valid_for_network(*n)).collect();
+    // Every address is valid on some network, mainnet addresses are not valid on any test network
+    // and testnet and signet always share their encoding.
+    assert!(valid.iter().any(|v| *v));
+    if valid[0] {
+        assert!(!valid[1] && !valid[2] && !valid[3]);
+    }
+    assert_eq!(valid[1], valid[2]);
+
+    for (network, valid) in networks.iter().zip(valid) {
+        assert_eq!(addr.clone().require_network(*network).is_ok(), valid);
+    }
+}
+
+fn main() {
+    loop {
+        fuzz!(|data| {
+            do_test(data);
+        });
+    }
+}
+
+#[cfg(all(test, fuzzing))]
+mod tests {
+    fn extend_vec_from_hex(hex: &str, out: &mut Vec<u8>) {
+        let mut b = 0;
+        for (idx, c) in hex.as_bytes().iter().enumerate() {
+            b <<= 4;
+            match *c {
+                b'A'..=b'F' => b |= c - b'A' + 10,
+                b'a'..=b'f' => b |= c - b'a' + 10,
+                b'0'..=b'9' => b |= c - b'0',
+                _ => panic!("Bad hex"),
+            }
+            if (idx & 1) == 1 {
+                out.push(b);
+                b = 0;
+            }
+        }
+    }
+
+    #[test]
+    fn duplicate_crash() {
+        let mut a = Vec::new();
+        extend_vec_from_hex("00", &mut a);
+        super::do_test(&a);
+    }
+}
@@ -191,6 +191,12 @@ pub trait HashEngine: Clone + Default {
     /// Add data to the hash engine.
     fn input(&mut self, data: &[u8]);
 
+    /// Add the bytes of another hash to the hash engine.
+    ///
+    /// This is the same as `engine.input(hash.as_ref())`, bytes are input in their internal order
+    /// not the (possibly reversed) display order.
+    fn input_hash<H: Hash>(&mut self, hash: &H) { self.input(hash.as_ref()) }
+
     /// Return the number of bytes already n_bytes_hashed(inputted).
     fn n_bytes_hashed(&self) -> usize;
 }
@@ -227,6 +233,24 @@ pub trait GeneralHash: Hash {
         }
         Self::from_engine(engine)
     }
+
+    /// Hashes all the data read from `reader`, until it returns EOF.
+    ///
+    /// The data is read in chunks so this can be used to hash large files or streams without
+    /// loading them into memory.
+    #[cfg(feature = "std")]
+    fn from_reader<R: std::io::Read>(mut reader: R) -> std::io::Result<Self> {
+        let mut engine = Self::engine();
+        let mut buf = [0u8; 4096];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => return Ok(Self::from_engine(engine)),
+                Ok(n) => engine.input(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
 }
 
 /// Trait which applies to hashes of all types.
@@ -322,6 +346,33 @@ mod tests {
         assert_eq!(h2.to_raw_hash(), h);
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader() {
+        use std::io::Cursor;
+
+        use crate::{sha256, GeneralHash as _};
+
+        // Larger than the internal buffer so it is read in several chunks.
+        let bytes = (0..10_000).map(|i| i as u8).collect::<Vec<u8>>();
+        let hash = sha256::Hash::from_reader(Cursor::new(&bytes)).unwrap();
+        assert_eq!(hash, sha256::Hash::hash(&bytes));
+
+        let hash = sha256d::Hash::from_reader(Cursor::new(&[])).unwrap();
+        assert_eq!(hash, sha256d::Hash::hash(&[]));
+    }
+
+    #[test]
+    fn input_hash() {
+        use crate::{sha256, HashEngine as _};
+
+        let mut engine = sha256::Hash::engine();
+        engine.input_hash(&DUMMY);
+        let mut manual = sha256::Hash::engine();
+        manual.input(DUMMY.as_byte_array());
+        assert_eq!(sha256::Hash::from_engine(engine), sha256::Hash::from_engine(manual));
+    }
+
     #[test]
     fn newtype_fmt_roundtrip() {
         let orig = DUMMY;
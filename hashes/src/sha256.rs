@@ -426,6 +426,11 @@ impl Midstate {
 impl HashEngine {
     /// Create a new [`HashEngine`] from a [`Midstate`].
     ///
+    /// Together with [`midstate`](crate::HashEngine::midstate) this can be used to checkpoint a
+    /// hash computation and resume it later. The midstate only covers whole blocks, so the engine
+    /// must be checkpointed when [`n_bytes_hashed`](crate::HashEngine::n_bytes_hashed) is a
+    /// multiple of the block size and that value passed in as `length`.
+    ///
     /// # Panics
     ///
     /// If `length` is not a multiple of the block size.
@@ -968,6 +973,23 @@ mod tests {
         assert_eq!(hash, sha256::Hash(HASH_EXPECTED));
     }
 
+    #[test]
+    fn midstate_resume() {
+        let data = (0..200).map(|i| i as u8).collect::<Vec<u8>>();
+        let (first, second) = data.split_at(128);
+
+        let mut engine = sha256::Hash::engine();
+        engine.input(first);
+        // Checkpoint the engine by serializing the midstate.
+        let serialized = engine.midstate().to_string();
+        let length = engine.n_bytes_hashed();
+
+        let midstate = serialized.parse::<Midstate>().unwrap();
+        let mut resumed = sha256::HashEngine::from_midstate(midstate, length);
+        resumed.input(second);
+        assert_eq!(sha256::Hash::from_engine(resumed), sha256::Hash::hash(&data));
+    }
+
     #[test]
     fn const_hash() {
         assert_eq!(Hash::hash(&[]), Hash::const_hash(&[]));
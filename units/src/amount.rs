@@ -267,6 +267,8 @@ impl std::error::Error for ParseAmountError {
 pub struct OutOfRangeError {
     is_signed: bool,
     is_greater_than_max: bool,
+    /// The range is limited to [`Amount::MAX_MONEY`] rather than the range of the type.
+    is_max_money: bool,
 }
 
 impl OutOfRangeError {
@@ -274,9 +276,10 @@ impl OutOfRangeError {
     ///
     /// This can be used to give a hint to the user which values are allowed.
     pub fn valid_range(&self) -> (i64, u64) {
-        match self.is_signed {
-            true => (i64::MIN, i64::MAX as u64),
-            false => (0, u64::MAX),
+        match (self.is_signed, self.is_max_money) {
+            (_, true) => (0, Amount::MAX_MONEY.to_sat()),
+            (true, false) => (i64::MIN, i64::MAX as u64),
+            (false, false) => (0, u64::MAX),
         }
     }
 
@@ -286,13 +289,16 @@ impl OutOfRangeError {
     /// Returns true if the input value was smaller than the minimum allowed value.
     pub fn is_below_min(&self) -> bool { !self.is_greater_than_max }
 
-    pub(crate) fn too_big(is_signed: bool) -> Self { Self { is_signed, is_greater_than_max: true } }
+    pub(crate) fn too_big(is_signed: bool) -> Self {
+        Self { is_signed, is_greater_than_max: true, is_max_money: false }
+    }
 
     pub(crate) fn too_small() -> Self {
         Self {
             // implied - negative() is used for the other
             is_signed: true,
             is_greater_than_max: false,
+            is_max_money: false,
         }
     }
 
@@ -301,13 +307,25 @@ impl OutOfRangeError {
             // implied - too_small() is used for the other
             is_signed: false,
             is_greater_than_max: false,
+            is_max_money: false,
         }
     }
+
+    pub(crate) fn above_max_money() -> Self {
+        Self { is_signed: false, is_greater_than_max: true, is_max_money: true }
+    }
 }
 
 impl fmt::Display for OutOfRangeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.is_greater_than_max {
+        if self.is_max_money {
+            // The limit is only used when parsing bitcoins so give it in the same unit.
+            write!(
+                f,
+                "the amount is greater than {} BTC",
+                Amount::MAX_MONEY.to_sat() / Amount::ONE_BTC.to_sat()
+            )
+        } else if self.is_greater_than_max {
             write!(f, "the amount is greater than {}", self.valid_range().1)
         } else {
             write!(f, "the amount is less than {}", self.valid_range().0)
@@ -631,8 +649,12 @@ internals::impl_from_infallible!(InnerParseError);
 impl InnerParseError {
     fn convert(self, is_signed: bool) -> ParseAmountError {
         match self {
-            Self::Overflow { is_negative } =>
-                OutOfRangeError { is_signed, is_greater_than_max: !is_negative }.into(),
+            Self::Overflow { is_negative } => OutOfRangeError {
+                is_signed,
+                is_greater_than_max: !is_negative,
+                is_max_money: false,
+            }
+            .into(),
             Self::TooPrecise(error) => ParseAmountError::TooPrecise(error),
             Self::MissingDigits(error) => ParseAmountError::MissingDigits(error),
             Self::InputTooLarge(len) => ParseAmountError::InputTooLarge(InputTooLargeError { len }),
@@ -661,6 +683,7 @@ struct FormatOptions {
     precision: Option<usize>,
     sign_plus: bool,
     sign_aware_zero_pad: bool,
+    group_digits: bool,
 }
 
 impl FormatOptions {
@@ -672,6 +695,7 @@ impl FormatOptions {
             precision: f.precision(),
             sign_plus: f.sign_plus(),
             sign_aware_zero_pad: f.sign_aware_zero_pad(),
+            group_digits: false,
         }
     }
 }
@@ -685,6 +709,7 @@ impl Default for FormatOptions {
             precision: None,
             sign_plus: false,
             sign_aware_zero_pad: false,
+            group_digits: false,
         }
     }
 }
@@ -708,6 +733,31 @@ fn repeat_char(f: &mut dyn fmt::Write, c: char, count: usize) -> fmt::Result {
     Ok(())
 }
 
+/// The separator written between groups of digits.
+const DIGIT_GROUP_SEPARATOR: char = ' ';
+
+/// Writes the `num_digits` least significant digits of `num` followed by `zeros` zeros, separating
+/// groups of three digits counting from the left or from the right.
+fn write_grouped_digits(
+    f: &mut dyn fmt::Write,
+    num: u64,
+    num_digits: usize,
+    zeros: usize,
+    from_right: bool,
+) -> fmt::Result {
+    let total = num_digits + zeros;
+    for i in 0..total {
+        let group_pos = if from_right { total - i } else { i };
+        if i > 0 && group_pos % 3 == 0 {
+            f.write_char(DIGIT_GROUP_SEPARATOR)?;
+        }
+        let digit =
+            if i < num_digits { num / 10u64.pow((num_digits - 1 - i) as u32) % 10 } else { 0 };
+        f.write_char(char::from(b'0' + digit as u8))?;
+    }
+    Ok(())
+}
+
 /// Format the given satoshi amount in the given denomination.
 fn fmt_satoshi_in(
     satoshi: u64,
@@ -763,6 +813,10 @@ fn fmt_satoshi_in(
         0
     };
     num_width += dec_width(num_before_decimal_point) + exp;
+    if options.group_digits {
+        num_width += (dec_width(num_before_decimal_point) + exp - 1) / 3;
+        num_width += total_decimals.saturating_sub(1) / 3;
+    }
     if options.sign_plus || negative {
         num_width += 1;
     }
@@ -798,17 +852,26 @@ fn fmt_satoshi_in(
         repeat_char(f, '0', left_pad)?;
     }
 
-    write!(f, "{}", num_before_decimal_point)?;
-
-    repeat_char(f, '0', exp)?;
+    if options.group_digits {
+        let width = dec_width(num_before_decimal_point);
+        write_grouped_digits(f, num_before_decimal_point, width, exp, true)?;
+    } else {
+        write!(f, "{}", num_before_decimal_point)?;
+        repeat_char(f, '0', exp)?;
+    }
 
     if total_decimals > 0 {
         write!(f, ".")?;
     }
-    if norm_nb_decimals > 0 {
-        write!(f, "{:0width$}", num_after_decimal_point, width = norm_nb_decimals)?;
+    if options.group_digits {
+        let (num, width) = (num_after_decimal_point, norm_nb_decimals);
+        write_grouped_digits(f, num, width, trailing_decimal_zeros, false)?;
+    } else {
+        if norm_nb_decimals > 0 {
+            write!(f, "{:0width$}", num_after_decimal_point, width = norm_nb_decimals)?;
+        }
+        repeat_char(f, '0', trailing_decimal_zeros)?;
     }
-    repeat_char(f, '0', trailing_decimal_zeros)?;
 
     if show_denom {
         write!(f, " {}", denom.as_str())?;
@@ -901,6 +964,28 @@ impl Amount {
         Ok(Amount::from_sat(satoshi))
     }
 
+    /// Parses a decimal string as a value in bitcoins.
+    ///
+    /// This is stricter than `Amount::from_str_in(s, Denomination::Bitcoin)` because amounts
+    /// greater than [`Amount::MAX_MONEY`] are rejected. Like it, more than 8 decimal places (i.e.
+    /// sub-satoshi precision) is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bitcoin_units::Amount;
+    /// assert_eq!(Amount::from_btc_str("0.00000001").unwrap(), Amount::ONE_SAT);
+    /// assert!(Amount::from_btc_str("0.000000001").is_err());
+    /// assert!(Amount::from_btc_str("21000001").is_err());
+    /// ```
+    pub fn from_btc_str(s: &str) -> Result<Amount, ParseAmountError> {
+        let amount = Amount::from_str_in(s, Denomination::Bitcoin)?;
+        if amount > Amount::MAX_MONEY {
+            return Err(OutOfRangeError::above_max_money().into());
+        }
+        Ok(amount)
+    }
+
     /// Parses amounts with denomination suffix like they are produced with
     /// [Self::to_string_with_denomination] or with [fmt::Display].
     /// If you want to parse only the amount without the denomination,
@@ -953,6 +1038,7 @@ impl Amount {
             sats_abs: self.to_sat(),
             is_negative: false,
             style: DisplayStyle::FixedDenomination { denomination, show_denomination: false },
+            group_digits: false,
         }
     }
 
@@ -965,6 +1051,7 @@ impl Amount {
             sats_abs: self.to_sat(),
             is_negative: false,
             style: DisplayStyle::DynamicDenomination,
+            group_digits: false,
         }
     }
 
@@ -1151,11 +1238,14 @@ impl TryFrom<SignedAmount> for Amount {
     fn try_from(value: SignedAmount) -> Result<Self, Self::Error> { value.to_unsigned() }
 }
 
+/// Sums the amounts.
+///
+/// # Panics
+///
+/// On overflow, in both debug and release builds, just like `+`. Use
+/// [`CheckedSum::checked_sum`] to handle overflow.
 impl core::iter::Sum for Amount {
-    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        let sats: u64 = iter.map(|amt| amt.0).sum();
-        Amount::from_sat(sats)
-    }
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self { iter.fold(Amount::ZERO, ops::Add::add) }
 }
 
 /// A helper/builder that displays amount with specified settings.
@@ -1179,6 +1269,8 @@ pub struct Display {
     is_negative: bool,
     /// How to display the value
     style: DisplayStyle,
+    /// Whether to separate groups of three digits
+    group_digits: bool,
 }
 
 impl Display {
@@ -1191,12 +1283,23 @@ impl Display {
         }
         self
     }
+
+    /// Makes subsequent calls to `Display::fmt` separate groups of three digits with a space.
+    ///
+    /// Digits before the decimal point are grouped from the right and digits after it from the
+    /// left, e.g. `21 000 000` or `0.001 000 00`. Trailing zeros are controlled by the precision
+    /// as usual, use `{:.8}` to always show all eight decimals of a bitcoin amount.
+    pub fn group_digits(mut self) -> Self {
+        self.group_digits = true;
+        self
+    }
 }
 
 impl fmt::Display for Display {
     #[rustfmt::skip]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let format_options = FormatOptions::from_formatter(f);
+        let mut format_options = FormatOptions::from_formatter(f);
+        format_options.group_digits = self.group_digits;
         match &self.style {
             DisplayStyle::FixedDenomination { show_denomination, denomination } => {
                 fmt_satoshi_in(self.sats_abs, self.is_negative, f, *denomination, *show_denomination, format_options)
@@ -1323,6 +1426,7 @@ impl SignedAmount {
             sats_abs: self.unsigned_abs().to_sat(),
             is_negative: self.is_negative(),
             style: DisplayStyle::FixedDenomination { denomination, show_denomination: false },
+            group_digits: false,
         }
     }
 
@@ -1335,6 +1439,7 @@ impl SignedAmount {
             sats_abs: self.unsigned_abs().to_sat(),
             is_negative: self.is_negative(),
             style: DisplayStyle::DynamicDenomination,
+            group_digits: false,
         }
     }
 
@@ -1560,10 +1665,15 @@ impl TryFrom<Amount> for SignedAmount {
     fn try_from(value: Amount) -> Result<Self, Self::Error> { value.to_signed() }
 }
 
+/// Sums the amounts.
+///
+/// # Panics
+///
+/// On overflow, in both debug and release builds, just like `+`. Use
+/// [`CheckedSum::checked_sum`] to handle overflow.
 impl core::iter::Sum for SignedAmount {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        let sats: i64 = iter.map(|amt| amt.0).sum();
-        SignedAmount::from_sat(sats)
+        iter.fold(SignedAmount::ZERO, ops::Add::add)
     }
 }
 
@@ -1974,7 +2084,7 @@ mod verification {
             if n1 >= 0 {
                 Ok(Amount::from_sat(n1.try_into().unwrap()))
             } else {
-                Err(OutOfRangeError { is_signed: false, is_greater_than_max: false })
+                Err(OutOfRangeError::negative())
             },
         );
     }
@@ -2059,7 +2169,7 @@ mod tests {
 
         let ua_max = Amount::MAX;
         let result = SignedAmount::try_from(ua_max);
-        assert_eq!(result, Err(OutOfRangeError { is_signed: true, is_greater_than_max: true }));
+        assert_eq!(result, Err(OutOfRangeError::too_big(true)));
     }
 
     #[test]
@@ -2070,7 +2180,7 @@ mod tests {
 
         let sa_negative = SignedAmount(-123);
         let result = Amount::try_from(sa_negative);
-        assert_eq!(result, Err(OutOfRangeError { is_signed: false, is_greater_than_max: false }));
+        assert_eq!(result, Err(OutOfRangeError::negative()));
     }
 
     #[test]
@@ -2189,6 +2299,37 @@ mod tests {
         assert_eq!(&btc(0.0012).to_float_in(D::Bitcoin).to_string(), "0.0012")
     }
 
+    #[test]
+    fn from_btc_str() {
+        assert_eq!(Amount::from_btc_str("0.00000001"), Ok(Amount::ONE_SAT));
+        assert_eq!(Amount::from_btc_str("21000000"), Ok(Amount::MAX_MONEY));
+        assert_eq!(Amount::from_btc_str("1.5"), Ok(Amount::from_sat(150_000_000)));
+
+        assert!(matches!(
+            Amount::from_btc_str("0.000000001"),
+            Err(ParseAmountError::TooPrecise(_))
+        ));
+        assert!(matches!(
+            Amount::from_btc_str("0.000000010"),
+            Err(ParseAmountError::TooPrecise(_))
+        ));
+        assert!(matches!(
+            Amount::from_btc_str("1.0.0"),
+            Err(ParseAmountError::InvalidCharacter(_))
+        ));
+
+        let err = Amount::from_btc_str("21000001").unwrap_err();
+        assert_eq!(err, ParseAmountError::OutOfRange(OutOfRangeError::above_max_money()));
+        match err {
+            ParseAmountError::OutOfRange(e) => {
+                assert!(e.is_above_max());
+                assert_eq!(e.valid_range(), (0, 2_100_000_000_000_000));
+                assert_eq!(e.to_string(), "the amount is greater than 21000000 BTC");
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     #[allow(clippy::inconsistent_digit_grouping)] // Group to show 100,000,000 sats per bitcoin.
     fn parsing() {
@@ -2306,6 +2447,41 @@ mod tests {
         assert!(buf.chars().all(|c| c == '0'));
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn display_group_digits() {
+        let btc = |sat: u64| Amount::from_sat(sat).display_in(Denomination::Bitcoin).group_digits();
+        assert_eq!(format!("{}", btc(100_000)), "0.001");
+        assert_eq!(format!("{:.8}", btc(100_000)), "0.001 000 00");
+        assert_eq!(format!("{}", btc(123_456_789)), "1.234 567 89");
+        assert_eq!(format!("{}", btc(2_100_000_000_000_000)), "21 000 000");
+        assert_eq!(format!("{:.8}", btc(2_100_000_000_000_000)), "21 000 000.000 000 00");
+        assert_eq!(format!("{}", btc(100_000).show_denomination()), "0.001 BTC");
+        assert_eq!(format!("{:>14}", btc(100_000).show_denomination()), "     0.001 BTC");
+        assert_eq!(format!("{:.4}", btc(0)), "0.000 0");
+
+        let sat = |sat: u64| Amount::from_sat(sat).display_in(Denomination::Satoshi).group_digits();
+        assert_eq!(format!("{}", sat(0)), "0");
+        assert_eq!(format!("{}", sat(999)), "999");
+        assert_eq!(format!("{}", sat(1000)), "1 000");
+        assert_eq!(format!("{}", sat(1_234_567)), "1 234 567");
+        assert_eq!(format!("{}", sat(100_000).show_denomination()), "100 000 satoshi");
+        assert_eq!(format!("{:>10}", sat(100_000)), "   100 000");
+
+        let signed = SignedAmount::from_sat(-1_234_567).display_in(Denomination::Satoshi);
+        assert_eq!(format!("{}", signed.group_digits()), "-1 234 567");
+        assert_eq!(
+            format!("{}", Amount::from_sat(1_234_567).display_dynamic().group_digits()),
+            "1 234 567 satoshi"
+        );
+
+        // Plain `Display` is unchanged.
+        assert_eq!(
+            format!("{}", Amount::from_sat(1_234_567).display_in(Denomination::Satoshi)),
+            "1234567"
+        );
+    }
+
     // Creates individual test functions to make it easier to find which check failed.
     macro_rules! check_format_non_negative {
         ($denom:ident; $($test_name:ident, $val:literal, $format_string:literal, $expected:literal);* $(;)?) => {
@@ -2698,6 +2874,13 @@ mod tests {
             .contains(&ParseAmountError::TooPrecise(TooPreciseError { position: 16 }).to_string()));
         let t: Result<T, serde_json::Error> = serde_json::from_str("{\"amt\": -1, \"samt\": 1}");
         assert!(t.unwrap_err().to_string().contains(&OutOfRangeError::negative().to_string()));
+        // More satoshis than fit in the integer types.
+        let t: Result<T, serde_json::Error> =
+            serde_json::from_str("{\"amt\": 200000000000, \"samt\": 1}");
+        assert!(t.unwrap_err().to_string().contains(&OutOfRangeError::too_big(false).to_string()));
+        let t: Result<T, serde_json::Error> =
+            serde_json::from_str("{\"amt\": 1, \"samt\": -100000000000}");
+        assert!(t.unwrap_err().to_string().contains(&OutOfRangeError::too_small().to_string()));
     }
 
     #[cfg(feature = "serde")]
@@ -2799,6 +2982,21 @@ mod tests {
         assert_eq!(SignedAmount::from_sat(1316), sum);
     }
 
+    #[test]
+    fn sum_amount_refs() {
+        let outputs = [Amount::from_sat(42), Amount::from_sat(1337)];
+        assert_eq!(outputs.iter().copied().sum::<Amount>(), Amount::from_sat(1379));
+        let outputs = [SignedAmount::from_sat(-42), SignedAmount::from_sat(1337)];
+        assert_eq!(outputs.iter().copied().sum::<SignedAmount>(), SignedAmount::from_sat(1295));
+    }
+
+    #[test]
+    #[should_panic]
+    fn sum_amounts_overflow() {
+        let amounts = [Amount::from_sat(u64::MAX), Amount::from_sat(1)];
+        let _ = amounts.into_iter().sum::<Amount>();
+    }
+
     #[test]
     fn checked_sum_amounts() {
         assert_eq!(Some(Amount::from_sat(0)), [].into_iter().checked_sum());
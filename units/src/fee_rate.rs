@@ -36,17 +36,24 @@ impl FeeRate {
 
     /// Minimum fee rate required to broadcast a transaction.
     ///
-    /// The value matches the default Bitcoin Core policy at the time of library release.
+    /// The value matches the default Bitcoin Core policy (`-minrelaytxfee`) at the time of library
+    /// release. Transactions paying a fee rate below this (`fee_rate < FeeRate::BROADCAST_MIN`) are
+    /// not relayed by nodes using the default policy.
+    #[doc(alias = "MIN_RELAY")]
     pub const BROADCAST_MIN: FeeRate = FeeRate::from_sat_per_vb_unchecked(1);
 
     /// Fee rate used to compute dust amount.
     pub const DUST: FeeRate = FeeRate::from_sat_per_vb_unchecked(3);
 
     /// Constructs `FeeRate` from satoshis per 1000 weight units.
+    ///
+    /// This is the internal representation so the conversion is exact.
     pub const fn from_sat_per_kwu(sat_kwu: u64) -> Self { FeeRate(sat_kwu) }
 
     /// Constructs `FeeRate` from satoshis per virtual bytes.
     ///
+    /// Since 1 sat/vB is exactly 250 sat/kwu the conversion never rounds.
+    ///
     /// # Errors
     ///
     /// Returns `None` on arithmetic overflow.
@@ -69,7 +76,17 @@ impl FeeRate {
     pub const fn to_sat_per_vb_floor(self) -> u64 { self.0 / (1000 / 4) }
 
     /// Converts to sat/vB rounding up.
-    pub const fn to_sat_per_vb_ceil(self) -> u64 { (self.0 + (1000 / 4 - 1)) / (1000 / 4) }
+    ///
+    /// Use this when the result is a lower bound, e.g. when checking a fee rate is above the
+    /// minimum relay fee rate.
+    pub const fn to_sat_per_vb_ceil(self) -> u64 {
+        let sat_vb = self.0 / (1000 / 4);
+        if self.0 % (1000 / 4) == 0 {
+            sat_vb
+        } else {
+            sat_vb + 1
+        }
+    }
 
     /// Checked multiplication.
     ///
@@ -158,6 +175,15 @@ mod tests {
         assert_eq!(750, FeeRate::DUST.to_sat_per_kwu());
     }
 
+    #[test]
+    fn broadcast_min() {
+        assert!(FeeRate::from_sat_per_vb_unchecked(1) >= FeeRate::BROADCAST_MIN);
+        assert!(FeeRate::from_sat_per_kwu(250) >= FeeRate::BROADCAST_MIN);
+        assert!(FeeRate::from_sat_per_kwu(249) < FeeRate::BROADCAST_MIN);
+        assert!(FeeRate::ZERO < FeeRate::BROADCAST_MIN);
+        assert!(FeeRate::DUST > FeeRate::BROADCAST_MIN);
+    }
+
     #[test]
     fn fee_rate_from_sat_per_vb_test() {
         let fee_rate = FeeRate::from_sat_per_vb(10).expect("expected feerate in sat/kwu");
@@ -189,6 +215,26 @@ mod tests {
         assert_eq!(2, fee_rate.to_sat_per_vb_ceil());
     }
 
+    #[test]
+    fn sat_per_vb_kwu_conversions() {
+        let fee_rate = FeeRate::from_sat_per_vb(1).unwrap();
+        assert_eq!(fee_rate, FeeRate::from_sat_per_kwu(250));
+        assert_eq!(fee_rate.to_sat_per_kwu(), 250);
+        assert_eq!(fee_rate.to_sat_per_vb_floor(), 1);
+        assert_eq!(fee_rate.to_sat_per_vb_ceil(), 1);
+
+        // Fractional sat/vB.
+        let fee_rate = FeeRate::from_sat_per_kwu(251);
+        assert_eq!(fee_rate.to_sat_per_vb_floor(), 1);
+        assert_eq!(fee_rate.to_sat_per_vb_ceil(), 2);
+        let fee_rate = FeeRate::from_sat_per_kwu(249);
+        assert_eq!(fee_rate.to_sat_per_vb_floor(), 0);
+        assert_eq!(fee_rate.to_sat_per_vb_ceil(), 1);
+
+        assert_eq!(FeeRate::ZERO.to_sat_per_vb_ceil(), 0);
+        assert_eq!(FeeRate::MAX.to_sat_per_vb_ceil(), u64::MAX / 250 + 1);
+    }
+
     #[test]
     fn checked_mul_test() {
         let fee_rate = FeeRate(10).checked_mul(10).expect("expected feerate in sat/kwu");
@@ -226,6 +226,21 @@ mod tests {
         assert_eq!(None, result);
     }
 
+    #[test]
+    fn sum() {
+        let weights = [Weight(1), Weight(2), Weight(3)];
+        assert_eq!(weights.iter().sum::<Weight>(), Weight(6));
+        assert_eq!(weights.into_iter().sum::<Weight>(), Weight(6));
+        assert_eq!(core::iter::empty::<Weight>().sum::<Weight>(), Weight::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "Weight addition error")]
+    fn sum_overflow() {
+        let weights = [Weight::MAX, Weight(1)];
+        let _ = weights.iter().sum::<Weight>();
+    }
+
     #[test]
     fn checked_sub() {
         let result = Weight(1).checked_sub(Weight(1)).expect("expected weight unit");
@@ -319,12 +334,17 @@ impl DivAssign<u64> for Weight {
     fn div_assign(&mut self, rhs: u64) { self.0 /= rhs }
 }
 
+/// Sums the weights.
+///
+/// # Panics
+///
+/// On overflow, in both debug and release builds. Use [`Weight::checked_add`] to handle overflow.
 impl core::iter::Sum for Weight {
     fn sum<I>(iter: I) -> Self
     where
         I: Iterator<Item = Self>,
     {
-        Weight(iter.map(Weight::to_wu).sum())
+        iter.fold(Weight::ZERO, |acc, w| acc.checked_add(w).expect("Weight addition error"))
     }
 }
 